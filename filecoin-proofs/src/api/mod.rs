@@ -26,11 +26,11 @@ use typenum::Unsigned;
 use crate::{
     commitment_reader::CommitmentReader,
     constants::{
-        DefaultBinaryTree, DefaultOctTree, DefaultPieceDomain, DefaultPieceHasher,
+        DefaultBinaryTree, DefaultPieceDomain, DefaultPieceHasher,
         MINIMUM_RESERVED_BYTES_FOR_PIECE_IN_FULLY_ALIGNED_SECTOR as MINIMUM_PIECE_SIZE,
     },
     parameters::public_params,
-    pieces::{get_piece_alignment, sum_piece_bytes_with_alignment},
+    pieces::{get_piece_alignment, sum_piece_bytes_with_alignment, EmptySource},
     types::{
         Commitment, MerkleTreeTrait, PaddedBytesAmount, PieceInfo, PoRepConfig,
         PoRepProofPartitions, ProverId, SealPreCommitPhase1Output, Ticket, UnpaddedByteIndex,
@@ -347,6 +347,46 @@ pub fn generate_piece_commitment<T: Read>(
     result
 }
 
+/// Recomputes the piece commitment for the unprocessed piece bytes stored at
+/// `piece_path` and returns whether it matches `piece_info.commitment`. A file
+/// shorter than `piece_info.size` is zero-filled up to that size before
+/// preprocessing, matching how pieces are padded when they are added to a
+/// sector. Returns an error if the file holds more than `piece_info.size`
+/// bytes.
+///
+/// # Arguments
+///
+/// * `piece_path` - path to a file containing the unprocessed (not bit-padded) piece bytes.
+/// * `piece_info` - the piece info (commitment and unpadded byte length) to check against.
+pub fn verify_piece_commitment<P: AsRef<Path>>(
+    piece_path: P,
+    piece_info: &PieceInfo,
+) -> Result<bool> {
+    trace!("verify_piece_commitment:start");
+
+    ensure_piece_size(piece_info.size)?;
+
+    let piece_file = File::open(&piece_path).with_context(|| {
+        format!(
+            "could not open piece_path={:?}",
+            piece_path.as_ref().display()
+        )
+    })?;
+    let piece_file_len = piece_file.metadata()?.len();
+    ensure!(
+        piece_file_len <= u64::from(piece_info.size),
+        "piece file ({} bytes) is larger than the piece size ({:?})",
+        piece_file_len,
+        piece_info.size,
+    );
+
+    let fill = EmptySource::new((u64::from(piece_info.size) - piece_file_len) as usize);
+    let computed = generate_piece_commitment(piece_file.chain(fill), piece_info.size)?;
+
+    trace!("verify_piece_commitment:finish");
+    Ok(computed.commitment == piece_info.commitment)
+}
+
 /// Computes a NUL-byte prefix and/or suffix for `source` using the provided
 /// `piece_lengths` and `piece_size` (such that the `source`, after
 /// preprocessing, will occupy a subtree of a merkle tree built using the bytes
@@ -421,7 +461,7 @@ where
     result
 }
 
-fn ensure_piece_size(piece_size: UnpaddedBytesAmount) -> Result<()> {
+pub(crate) fn ensure_piece_size(piece_size: UnpaddedBytesAmount) -> Result<()> {
     ensure!(
         piece_size >= UnpaddedBytesAmount(MINIMUM_PIECE_SIZE),
         "Piece must be at least {} bytes",
@@ -503,7 +543,7 @@ fn verify_store(config: &StoreConfig, arity: usize, required_configs: usize) ->
             store_path.display()
         );
 
-        let store_len = config.size.expect("disk store size not configured");
+        let store_len = config.size.context("disk store size not configured")?;
         for config in &configs {
             ensure!(
                 DiskStore::<DefaultPieceDomain>::is_consistent(store_len, arity, &config,)?,
@@ -512,12 +552,9 @@ fn verify_store(config: &StoreConfig, arity: usize, required_configs: usize) ->
             );
         }
     } else {
+        let store_len = config.size.context("disk store size not configured")?;
         ensure!(
-            DiskStore::<DefaultPieceDomain>::is_consistent(
-                config.size.expect("disk store size not configured"),
-                arity,
-                &config,
-            )?,
+            DiskStore::<DefaultPieceDomain>::is_consistent(store_len, arity, &config,)?,
             "Store is inconsistent: {:?}",
             store_path
         );
@@ -567,7 +604,7 @@ fn verify_level_cache_store<Tree: MerkleTreeTrait>(config: &StoreConfig) -> Resu
             store_path.display()
         );
 
-        let store_len = config.size.expect("disk store size not configured");
+        let store_len = config.size.context("disk store size not configured")?;
         for config in &configs {
             ensure!(
                 LevelCacheStore::<DefaultPieceDomain, File>::is_consistent(
@@ -580,9 +617,10 @@ fn verify_level_cache_store<Tree: MerkleTreeTrait>(config: &StoreConfig) -> Resu
             );
         }
     } else {
+        let store_len = config.size.context("disk store size not configured")?;
         ensure!(
             LevelCacheStore::<DefaultPieceDomain, File>::is_consistent(
-                config.size.expect("disk store size not configured"),
+                store_len,
                 Tree::Arity::to_usize(),
                 &config,
             )?,
@@ -594,6 +632,64 @@ fn verify_level_cache_store<Tree: MerkleTreeTrait>(config: &StoreConfig) -> Resu
     Ok(())
 }
 
+/// Checks that every store referenced by `t_aux` (the layer labels, tree_d, tree_c
+/// and tree_r_last) exists in `cache_path` and is consistent with its configured
+/// size, naming the first store that is missing or inconsistent.
+///
+/// # Arguments
+///
+/// * `t_aux` - the temporary aux read from the sector's cache directory.
+/// * `cache_path` - path to the sector's cache directory.
+pub fn validate_t_aux_stores<P: AsRef<Path>, Tree: MerkleTreeTrait>(
+    t_aux: &TemporaryAux<Tree, DefaultPieceHasher>,
+    cache_path: P,
+) -> Result<()> {
+    let cache = cache_path.as_ref().to_path_buf();
+    let mut t_aux = t_aux.clone();
+    t_aux.set_cache_path(&cache);
+
+    // Verify all stores/labels within the Labels object.
+    t_aux
+        .labels
+        .verify_stores(verify_store, &cache)
+        .context("missing or inconsistent label store")?;
+
+    // Verify each tree disk store.
+    verify_store(
+        &t_aux.tree_d_config,
+        <DefaultBinaryTree as MerkleTreeTrait>::Arity::to_usize(),
+        get_base_tree_count::<Tree>(),
+    )
+    .with_context(|| {
+        format!(
+            "missing or inconsistent tree store {}",
+            t_aux.tree_d_config.id
+        )
+    })?;
+    verify_tree_c_store::<Tree>(&t_aux.tree_c_config)?;
+    verify_tree_r_last_store::<Tree>(&t_aux.tree_r_last_config)?;
+
+    Ok(())
+}
+
+// Verifies the (possibly split) tree_c DiskStore specified by a config, naming it
+// if it is missing or inconsistent.
+fn verify_tree_c_store<Tree: MerkleTreeTrait>(config: &StoreConfig) -> Result<()> {
+    verify_store(
+        config,
+        Tree::Arity::to_usize(),
+        get_base_tree_count::<Tree>(),
+    )
+    .with_context(|| format!("missing or inconsistent tree store {}", config.id))
+}
+
+// Verifies the (possibly split) tree_r_last LevelCacheStore specified by a config,
+// naming it if it is missing or inconsistent.
+fn verify_tree_r_last_store<Tree: MerkleTreeTrait>(config: &StoreConfig) -> Result<()> {
+    verify_level_cache_store::<Tree>(config)
+        .with_context(|| format!("missing or inconsistent tree store {}", config.id))
+}
+
 // Checks for the existence of the tree d store, the replica, and all generated labels.
 pub fn validate_cache_for_precommit_phase2<R, T, Tree: MerkleTreeTrait>(
     cache_path: R,
@@ -687,22 +783,7 @@ where
         res
     };
 
-    // Verify all stores/labels within the Labels object.
-    let cache = cache_path.as_ref().to_path_buf();
-    t_aux.labels.verify_stores(verify_store, &cache)?;
-
-    // Verify each tree disk store.
-    verify_store(
-        &t_aux.tree_d_config,
-        <DefaultBinaryTree as MerkleTreeTrait>::Arity::to_usize(),
-        get_base_tree_count::<Tree>(),
-    )?;
-    verify_store(
-        &t_aux.tree_c_config,
-        <DefaultOctTree as MerkleTreeTrait>::Arity::to_usize(),
-        get_base_tree_count::<Tree>(),
-    )?;
-    verify_level_cache_store::<DefaultOctTree>(&t_aux.tree_r_last_config)?;
+    validate_t_aux_stores(&t_aux, &cache_path)?;
 
     info!("validate_cache_for_precommit:finish");
     Ok(())
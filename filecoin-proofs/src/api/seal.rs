@@ -1,15 +1,20 @@
 use std::fs::{self, metadata, File, OpenOptions};
-use std::io::Write;
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 
 use anyhow::{ensure, Context, Result};
 use bellperson::groth16;
 use bincode::{deserialize, serialize};
 use blstrs::{Bls12, Scalar as Fr};
-use filecoin_hashers::{Domain, Hasher};
+use filecoin_hashers::{Domain, HashFunction, Hasher};
 use log::{info, trace};
 use memmap::MmapOptions;
-use merkletree::store::{DiskStore, Store, StoreConfig};
+use merkletree::{
+    hash::Algorithm,
+    merkle::get_merkle_tree_len,
+    store::{DiskStore, Store, StoreConfig},
+};
 use rayon::prelude::*;
 use sha2::{Digest, Sha256};
 use storage_proofs_core::{
@@ -17,35 +22,43 @@ use storage_proofs_core::{
     compound_proof::{self, CompoundProof},
     drgraph::Graph,
     measurements::{measure_op, Operation},
-    merkle::{create_base_merkle_tree, BinaryMerkleTree, MerkleTreeTrait},
+    merkle::{
+        create_base_merkle_tree, get_base_tree_count, split_config, BinaryMerkleTree,
+        MerkleTreeTrait,
+    },
     multi_proof::MultiProof,
     parameter_cache::SRS_MAX_PROOFS_TO_AGGREGATE,
     proof::ProofScheme,
     sector::SectorId,
-    util::default_rows_to_discard,
+    util::{default_rows_to_discard, NODE_SIZE},
     Data,
 };
 use storage_proofs_porep::stacked::{
-    self, generate_replica_id, ChallengeRequirements, StackedCompound, StackedDrg, Tau,
-    TemporaryAux, TemporaryAuxCache,
+    self, generate_replica_id, ChallengeRequirements, Labels, PersistentAux, StackedCompound,
+    StackedDrg, Tau, TemporaryAux, TemporaryAuxCache,
 };
+use typenum::Unsigned;
 
 use crate::{
-    api::{as_safe_commitment, commitment_from_fr, get_base_tree_leafs, get_base_tree_size},
+    api::{
+        as_safe_commitment, commitment_from_fr, get_base_tree_leafs, get_base_tree_size,
+        stream_merkle_root, validate_t_aux_stores, verify_tree_c_store, verify_tree_r_last_store,
+    },
     caches::{
         get_stacked_params, get_stacked_srs_key, get_stacked_srs_verifier_key,
         get_stacked_verifying_key,
     },
     constants::{
-        DefaultBinaryTree, DefaultPieceDomain, DefaultPieceHasher, POREP_MINIMUM_CHALLENGES,
-        SINGLE_PARTITION_PROOF_LEN,
+        DefaultBinaryTree, DefaultPieceDomain, DefaultPieceHasher, LAYERS,
+        POREP_MINIMUM_CHALLENGES, SINGLE_PARTITION_PROOF_LEN,
     },
     parameters::setup_params,
     pieces::{self, verify_pieces},
     types::{
         AggregateSnarkProof, Commitment, PaddedBytesAmount, PieceInfo, PoRepConfig,
         PoRepProofPartitions, ProverId, SealCommitOutput, SealCommitPhase1Output,
-        SealPreCommitOutput, SealPreCommitPhase1Output, SectorSize, Ticket, BINARY_ARITY,
+        SealPreCommitOutput, SealPreCommitPhase1Output, SectorSize, TAuxDescription, Ticket,
+        BINARY_ARITY,
     },
 };
 
@@ -67,6 +80,8 @@ where
 {
     info!("seal_pre_commit_phase1:start: {:?}", sector_id);
 
+    porep_config.validate_supported()?;
+
     // Sanity check all input path types.
     ensure!(
         metadata(in_path.as_ref())?.is_file(),
@@ -210,6 +225,8 @@ where
 {
     info!("seal_pre_commit_phase2:start");
 
+    porep_config.validate_supported()?;
+
     // Sanity check all input path types.
     ensure!(
         metadata(cache_path.as_ref())?.is_dir(),
@@ -335,6 +352,8 @@ pub fn seal_commit_phase1<T: AsRef<Path>, Tree: 'static + MerkleTreeTrait>(
 ) -> Result<SealCommitPhase1Output<Tree>> {
     info!("seal_commit_phase1:start: {:?}", sector_id);
 
+    porep_config.validate_supported()?;
+
     // Sanity check all input path types.
     ensure!(
         metadata(cache_path.as_ref())?.is_dir(),
@@ -370,10 +389,14 @@ pub fn seal_commit_phase1<T: AsRef<Path>, Tree: 'static + MerkleTreeTrait>(
         let mut res: TemporaryAux<_, _> = deserialize(&t_aux_bytes)?;
 
         // Switch t_aux to the passed in cache_path
-        res.set_cache_path(cache_path);
+        res.set_cache_path(&cache_path);
         res
     };
 
+    // Fail early, naming the missing store, rather than part way through
+    // restoring the tree stores below.
+    validate_t_aux_stores(&t_aux, &cache_path)?;
+
     // Convert TemporaryAux to TemporaryAuxCache, which instantiates all
     // elements based on the configs stored in TemporaryAux.
     let t_aux_cache: TemporaryAuxCache<Tree, DefaultPieceHasher> =
@@ -381,7 +404,7 @@ pub fn seal_commit_phase1<T: AsRef<Path>, Tree: 'static + MerkleTreeTrait>(
             .context("failed to restore contents of t_aux")?;
 
     let comm_r_safe = as_safe_commitment(&comm_r, "comm_r")?;
-    let comm_d_safe = DefaultPieceDomain::try_from_bytes(&comm_d)?;
+    let comm_d_safe: DefaultPieceDomain = as_safe_commitment(&comm_d, "comm_d")?;
 
     let replica_id = generate_replica_id::<Tree::Hasher, _>(
         &prover_id,
@@ -458,6 +481,8 @@ pub fn seal_commit_phase2<Tree: 'static + MerkleTreeTrait>(
 ) -> Result<SealCommitOutput> {
     info!("seal_commit_phase2:start: {:?}", sector_id);
 
+    porep_config.validate_supported()?;
+
     let SealCommitPhase1Output {
         vanilla_proofs,
         comm_d,
@@ -471,7 +496,7 @@ pub fn seal_commit_phase2<Tree: 'static + MerkleTreeTrait>(
     ensure!(comm_r != [0; 32], "Invalid all zero commitment (comm_r)");
 
     let comm_r_safe = as_safe_commitment(&comm_r, "comm_r")?;
-    let comm_d_safe = DefaultPieceDomain::try_from_bytes(&comm_d)?;
+    let comm_d_safe: DefaultPieceDomain = as_safe_commitment(&comm_d, "comm_d")?;
 
     let public_inputs = stacked::PublicInputs {
         replica_id,
@@ -583,7 +608,7 @@ pub fn get_seal_inputs<Tree: 'static + MerkleTreeTrait>(
     );
 
     let comm_r_safe = as_safe_commitment(&comm_r, "comm_r")?;
-    let comm_d_safe = DefaultPieceDomain::try_from_bytes(&comm_d)?;
+    let comm_d_safe: DefaultPieceDomain = as_safe_commitment(&comm_d, "comm_d")?;
 
     let public_inputs = stacked::PublicInputs {
         replica_id,
@@ -892,6 +917,7 @@ pub fn verify_aggregate_seal_commit_proofs<Tree: 'static + MerkleTreeTrait>(
 ///
 /// * `porep_config` - this sector's porep config that contains the number of bytes in the sector.
 /// * `piece_infos` - the piece info (commitment and byte length) for each piece in this sector.
+///    If empty, the `comm_d` of an all-zero sector is returned.
 pub fn compute_comm_d(sector_size: SectorSize, piece_infos: &[PieceInfo]) -> Result<Commitment> {
     info!("compute_comm_d:start");
 
@@ -901,6 +927,405 @@ pub fn compute_comm_d(sector_size: SectorSize, piece_infos: &[PieceInfo]) -> Res
     result
 }
 
+/// Returns whether the sealed replicas using the caches at `cache_path_a` and
+/// `cache_path_b` have the same `comm_r`, derived from the `comm_c` and
+/// `comm_r_last` stored in each cache's `p_aux`.
+///
+/// The replicas themselves are never read. This is only sufficient when both
+/// caches are trusted to belong to (and be consistent with) their replicas;
+/// otherwise the replica bytes must be compared directly.
+///
+/// # Arguments
+///
+/// * `cache_path_a` - path to the cache directory of the first replica.
+/// * `cache_path_b` - path to the cache directory of the second replica.
+pub fn replicas_equal_by_commitment<R, S, Tree>(cache_path_a: R, cache_path_b: S) -> Result<bool>
+where
+    R: AsRef<Path>,
+    S: AsRef<Path>,
+    Tree: 'static + MerkleTreeTrait,
+{
+    info!("replicas_equal_by_commitment:start");
+
+    let comm_r_a = comm_r_from_p_aux::<_, Tree>(cache_path_a)?;
+    let comm_r_b = comm_r_from_p_aux::<_, Tree>(cache_path_b)?;
+
+    info!("replicas_equal_by_commitment:finish");
+    Ok(comm_r_a == comm_r_b)
+}
+
+/// Errors unless the `p_aux` files in `cache_path_a` and `cache_path_b` hold the
+/// same `comm_c`, naming both cache directories in the error. This catches
+/// mismatched cache directories before they are used together.
+///
+/// # Arguments
+///
+/// * `cache_path_a` - path to the first cache directory.
+/// * `cache_path_b` - path to the second cache directory.
+pub fn assert_matching_comm_c<R, S, Tree>(cache_path_a: R, cache_path_b: S) -> Result<()>
+where
+    R: AsRef<Path>,
+    S: AsRef<Path>,
+    Tree: 'static + MerkleTreeTrait,
+{
+    info!("assert_matching_comm_c:start");
+
+    let p_aux_a = read_p_aux::<_, Tree>(&cache_path_a)?;
+    let p_aux_b = read_p_aux::<_, Tree>(&cache_path_b)?;
+    ensure!(
+        p_aux_a.comm_c == p_aux_b.comm_c,
+        "comm_c mismatch between cache {:?} and cache {:?}",
+        cache_path_a.as_ref(),
+        cache_path_b.as_ref(),
+    );
+
+    info!("assert_matching_comm_c:finish");
+    Ok(())
+}
+
+/// Deserializes the `t_aux` in `cache_path` and returns a summary of the store
+/// configs it references. The stores themselves are neither opened nor mapped.
+///
+/// # Arguments
+///
+/// * `cache_path` - path to the cache directory containing `t_aux`.
+pub fn describe_t_aux<T: AsRef<Path>, Tree: 'static + MerkleTreeTrait>(
+    cache_path: T,
+) -> Result<TAuxDescription> {
+    info!("describe_t_aux:start");
+
+    let t_aux = read_t_aux::<_, Tree>(cache_path)?;
+    let description = TAuxDescription::from(&t_aux);
+
+    info!("describe_t_aux:finish");
+    Ok(description)
+}
+
+/// Returns whether the replica at `replica_path`, together with `comm_c`, commits
+/// to `comm_r`, without requiring the sector's cache directory.
+///
+/// The replica is read once, sequentially, and the tree_r_last root is computed
+/// level by level as nodes arrive, holding at most `arity` pending nodes per tree
+/// level in memory. Every replica node is still hashed, so the CPU cost is that of
+/// building tree_r_last, but no tree is written or kept in memory.
+///
+/// # Arguments
+///
+/// * `porep_config` - this sector's porep config that contains the number of bytes in the sector.
+/// * `replica_path` - path to the sealed replica.
+/// * `comm_c` - the expected `comm_c` of the sector.
+/// * `comm_r` - the expected `comm_r` of the sector.
+pub fn verify_comm_r_streaming<T: AsRef<Path>, Tree: 'static + MerkleTreeTrait>(
+    porep_config: PoRepConfig,
+    replica_path: T,
+    comm_c: Commitment,
+    comm_r: Commitment,
+) -> Result<bool> {
+    info!("verify_comm_r_streaming:start");
+
+    let nodes_count = porep_config.nodes_count()?;
+    let replica_len = metadata(replica_path.as_ref())
+        .with_context(|| {
+            format!(
+                "could not read metadata of replica={:?}",
+                replica_path.as_ref()
+            )
+        })?
+        .len();
+    ensure!(
+        replica_len == u64::from(porep_config.sector_size),
+        "replica length {} does not match sector size {}",
+        replica_len,
+        u64::from(porep_config.sector_size),
+    );
+
+    let comm_c_safe: <Tree::Hasher as Hasher>::Domain = as_safe_commitment(&comm_c, "comm_c")?;
+    let comm_r_safe: <Tree::Hasher as Hasher>::Domain = as_safe_commitment(&comm_r, "comm_r")?;
+
+    // The arity of each tree level from the leaves up: the base tree levels,
+    // followed by the sub and top tree levels for compound tree shapes.
+    let base_arity = Tree::Arity::to_usize();
+    let mut arities = Vec::new();
+    let mut width = nodes_count / get_base_tree_count::<Tree>();
+    while width > 1 {
+        ensure!(
+            width % base_arity == 0,
+            "base tree width {} is not a power of the tree arity {}",
+            width,
+            base_arity,
+        );
+        arities.push(base_arity);
+        width /= base_arity;
+    }
+    for arity in &[
+        Tree::SubTreeArity::to_usize(),
+        Tree::TopTreeArity::to_usize(),
+    ] {
+        if *arity > 0 {
+            arities.push(*arity);
+        }
+    }
+
+    let file = File::open(replica_path.as_ref())
+        .with_context(|| format!("could not open replica={:?}", replica_path.as_ref()))?;
+    let comm_r_last =
+        stream_merkle_root::<Tree::Hasher, _>(BufReader::new(file), nodes_count, &arities, true)
+            .with_context(|| format!("invalid replica={:?}", replica_path.as_ref()))?;
+    let computed_comm_r = <Tree::Hasher as Hasher>::Function::hash2(&comm_c_safe, &comm_r_last);
+
+    info!("verify_comm_r_streaming:finish");
+    Ok(computed_comm_r == comm_r_safe)
+}
+
+/// Returns the `comm_r_last` (the root of tree_r_last) stored in the `p_aux`
+/// of `cache_path`.
+///
+/// # Arguments
+///
+/// * `cache_path` - path to the sector's cache directory.
+pub fn read_comm_r_last<T: AsRef<Path>, Tree: 'static + MerkleTreeTrait>(
+    cache_path: T,
+) -> Result<Commitment> {
+    info!("read_comm_r_last:start");
+
+    let p_aux = read_p_aux::<_, Tree>(cache_path)?;
+    let comm_r_last = commitment_from_fr(p_aux.comm_r_last.into());
+
+    info!("read_comm_r_last:finish");
+    Ok(comm_r_last)
+}
+
+/// Reconstructs a lost `p_aux` in `cache_path` from the roots of the tree_c and
+/// tree_r_last stores referenced by the cache's `t_aux`.
+///
+/// The stores must still be present and consistent with their `t_aux` configs.
+/// An existing `p_aux` is never overwritten.
+///
+/// # Arguments
+///
+/// * `cache_path` - path to the sector's cache directory.
+pub fn rebuild_p_aux<T: AsRef<Path>, Tree: 'static + MerkleTreeTrait>(cache_path: T) -> Result<()> {
+    info!("rebuild_p_aux:start");
+
+    let p_aux_path = cache_path.as_ref().join(CacheKey::PAux.to_string());
+    ensure!(
+        !p_aux_path.exists(),
+        "refusing to overwrite existing p_aux={:?}",
+        p_aux_path
+    );
+
+    let mut t_aux = read_t_aux::<_, Tree>(&cache_path)?;
+    t_aux.set_cache_path(&cache_path);
+
+    verify_tree_c_store::<Tree>(&t_aux.tree_c_config)?;
+    verify_tree_r_last_store::<Tree>(&t_aux.tree_r_last_config)?;
+
+    let p_aux = PersistentAux {
+        comm_c: read_tree_root::<Tree>(&t_aux.tree_c_config)?,
+        comm_r_last: read_tree_root::<Tree>(&t_aux.tree_r_last_config)?,
+    };
+
+    let mut f_p_aux = File::create(&p_aux_path)
+        .with_context(|| format!("could not create file p_aux={:?}", p_aux_path))?;
+    let p_aux_bytes = serialize(&p_aux)?;
+    f_p_aux
+        .write_all(&p_aux_bytes)
+        .with_context(|| format!("could not write to file p_aux={:?}", p_aux_path))?;
+
+    info!("rebuild_p_aux:finish");
+    Ok(())
+}
+
+/// Reconstructs a lost `t_aux` in `cache_path` for a sector of `sector_size`.
+///
+/// The store configs are derived the same way seal pre commit phase2 derives
+/// them, whether or not the label, tree_d and tree_c stores are still present,
+/// so a cache that has already been cleared can be recovered too. The
+/// tree_r_last store must be present, consistent and match the `comm_r_last`
+/// of the cache's `p_aux`; tree_c is checked against `comm_c` only while it is
+/// present. An existing `t_aux` is never overwritten.
+///
+/// # Arguments
+///
+/// * `cache_path` - path to the sector's cache directory.
+/// * `sector_size` - size of the sealed sector.
+pub fn rebuild_t_aux<T: AsRef<Path>, Tree: 'static + MerkleTreeTrait>(
+    cache_path: T,
+    sector_size: SectorSize,
+) -> Result<()> {
+    info!("rebuild_t_aux:start");
+
+    let t_aux_path = cache_path.as_ref().join(CacheKey::TAux.to_string());
+    ensure!(
+        !t_aux_path.exists(),
+        "refusing to overwrite existing t_aux={:?}",
+        t_aux_path
+    );
+
+    let sector_bytes = u64::from(sector_size);
+    let layers = *LAYERS
+        .read()
+        .expect("LAYERS poisoned")
+        .get(&sector_bytes)
+        .with_context(|| format!("no layer count configured for sector size {}", sector_bytes))?;
+
+    let nodes_count = sector_bytes as usize / NODE_SIZE;
+    let tree_count = get_base_tree_count::<Tree>();
+    ensure!(
+        nodes_count % tree_count == 0,
+        "sector size {} cannot be split into {} base trees",
+        sector_bytes,
+        tree_count
+    );
+    let base_tree_nodes_count = nodes_count / tree_count;
+    let arity = Tree::Arity::to_usize();
+
+    let labels = (1..=layers)
+        .map(|layer| {
+            let mut config = StoreConfig::new(
+                cache_path.as_ref(),
+                CacheKey::label_layer(layer),
+                default_rows_to_discard(nodes_count, BINARY_ARITY),
+            );
+            config.size = Some(nodes_count);
+            config
+        })
+        .collect();
+
+    let mut tree_d_config = StoreConfig::new(
+        cache_path.as_ref(),
+        CacheKey::CommDTree.to_string(),
+        default_rows_to_discard(base_tree_nodes_count, BINARY_ARITY),
+    );
+    tree_d_config.size = Some(get_merkle_tree_len(nodes_count, BINARY_ARITY)?);
+
+    let mut tree_r_last_config = StoreConfig::new(
+        cache_path.as_ref(),
+        CacheKey::CommRLastTree.to_string(),
+        default_rows_to_discard(base_tree_nodes_count, arity),
+    );
+    tree_r_last_config.size = Some(get_merkle_tree_len(base_tree_nodes_count, arity)?);
+
+    let mut tree_c_config = StoreConfig::new(
+        cache_path.as_ref(),
+        CacheKey::CommCTree.to_string(),
+        default_rows_to_discard(base_tree_nodes_count, arity),
+    );
+    tree_c_config.size = Some(get_merkle_tree_len(base_tree_nodes_count, arity)?);
+
+    let t_aux = TemporaryAux::<Tree, DefaultPieceHasher> {
+        labels: Labels::new(labels),
+        tree_d_config,
+        tree_r_last_config,
+        tree_c_config,
+        _g: PhantomData,
+    };
+
+    let p_aux = read_p_aux::<_, Tree>(&cache_path)?;
+    verify_tree_r_last_store::<Tree>(&t_aux.tree_r_last_config)?;
+    ensure!(
+        read_tree_root::<Tree>(&t_aux.tree_r_last_config)? == p_aux.comm_r_last,
+        "tree store {} does not match comm_r_last in p_aux",
+        t_aux.tree_r_last_config.id
+    );
+
+    // tree_c is removed when the cache is cleared, so it is only checked while present.
+    let tree_c_present = split_config(t_aux.tree_c_config.clone(), tree_count)?
+        .iter()
+        .any(|config| StoreConfig::data_path(&config.path, &config.id).exists());
+    if tree_c_present {
+        verify_tree_c_store::<Tree>(&t_aux.tree_c_config)?;
+        ensure!(
+            read_tree_root::<Tree>(&t_aux.tree_c_config)? == p_aux.comm_c,
+            "tree store {} does not match comm_c in p_aux",
+            t_aux.tree_c_config.id
+        );
+    }
+
+    let mut f_t_aux = File::create(&t_aux_path)
+        .with_context(|| format!("could not create file t_aux={:?}", t_aux_path))?;
+    let t_aux_bytes = serialize(&t_aux)?;
+    f_t_aux
+        .write_all(&t_aux_bytes)
+        .with_context(|| format!("could not write to file t_aux={:?}", t_aux_path))?;
+
+    info!("rebuild_t_aux:finish");
+    Ok(())
+}
+
+/// Returns the root of the tree stored under `config`, reading the root of each
+/// base tree store (its last node) and combining them through the sub and top
+/// tree levels of `Tree`.
+fn read_tree_root<Tree: MerkleTreeTrait>(
+    config: &StoreConfig,
+) -> Result<<Tree::Hasher as Hasher>::Domain> {
+    let mut roots = Vec::with_capacity(get_base_tree_count::<Tree>());
+    for config in split_config(config.clone(), get_base_tree_count::<Tree>())? {
+        let store_path = StoreConfig::data_path(&config.path, &config.id);
+        let mut file = File::open(&store_path)
+            .with_context(|| format!("could not open tree store={:?}", store_path))?;
+        file.seek(SeekFrom::End(-(NODE_SIZE as i64)))
+            .with_context(|| format!("could not seek to root of tree store={:?}", store_path))?;
+
+        let mut buf = [0u8; NODE_SIZE];
+        file.read_exact(&mut buf)
+            .with_context(|| format!("could not read root of tree store={:?}", store_path))?;
+        roots.push(<Tree::Hasher as Hasher>::Domain::try_from_bytes(&buf)?);
+    }
+
+    let mut height = 0;
+    for arity in &[
+        Tree::SubTreeArity::to_usize(),
+        Tree::TopTreeArity::to_usize(),
+    ] {
+        if *arity > 0 {
+            roots = roots
+                .chunks(*arity)
+                .map(|nodes| {
+                    <Tree::Hasher as Hasher>::Function::default().multi_node(nodes, height)
+                })
+                .collect();
+            height += 1;
+        }
+    }
+    ensure!(
+        roots.len() == 1,
+        "tree stores for {} did not combine into a single root",
+        config.id
+    );
+
+    Ok(roots[0])
+}
+
+fn read_p_aux<T: AsRef<Path>, Tree: MerkleTreeTrait>(
+    cache_path: T,
+) -> Result<PersistentAux<<Tree::Hasher as Hasher>::Domain>> {
+    let p_aux_path = cache_path.as_ref().join(CacheKey::PAux.to_string());
+    let p_aux_bytes = fs::read(&p_aux_path)
+        .with_context(|| format!("could not read file p_aux={:?}", p_aux_path))?;
+
+    deserialize(&p_aux_bytes)
+        .with_context(|| format!("could not deserialize file p_aux={:?}", p_aux_path))
+}
+
+fn read_t_aux<T: AsRef<Path>, Tree: MerkleTreeTrait>(
+    cache_path: T,
+) -> Result<TemporaryAux<Tree, DefaultPieceHasher>> {
+    let t_aux_path = cache_path.as_ref().join(CacheKey::TAux.to_string());
+    let t_aux_bytes = fs::read(&t_aux_path)
+        .with_context(|| format!("could not read file t_aux={:?}", t_aux_path))?;
+
+    deserialize(&t_aux_bytes)
+        .with_context(|| format!("could not deserialize file t_aux={:?}", t_aux_path))
+}
+
+fn comm_r_from_p_aux<T: AsRef<Path>, Tree: MerkleTreeTrait>(cache_path: T) -> Result<Commitment> {
+    let p_aux = read_p_aux::<_, Tree>(cache_path)?;
+    let comm_r = <Tree::Hasher as Hasher>::Function::hash2(&p_aux.comm_c, &p_aux.comm_r_last);
+
+    Ok(commitment_from_fr(comm_r.into()))
+}
+
 /// Verifies the output of some previously-run seal operation.
 ///
 /// # Arguments
@@ -1,14 +1,34 @@
+use std::fs::{metadata, File};
+use std::io::{BufReader, Read};
 use std::mem::size_of;
+use std::path::Path;
 
-use anyhow::{Context, Result};
+use anyhow::{ensure, Context, Result};
 use blstrs::Scalar as Fr;
-use filecoin_hashers::{Domain, Hasher};
+use filecoin_hashers::{
+    blake2s::Blake2sHasher, poseidon::PoseidonHasher, sha256::Sha256Hasher, Domain, Hasher,
+};
 use fr32::{bytes_into_fr, fr_into_bytes};
-use merkletree::merkle::{get_merkle_tree_leafs, get_merkle_tree_len};
-use storage_proofs_core::merkle::{get_base_tree_count, MerkleTreeTrait};
+use memmap::MmapOptions;
+use merkletree::{
+    hash::Algorithm,
+    merkle::{get_merkle_tree_cache_size, get_merkle_tree_leafs, get_merkle_tree_len},
+};
+use rayon::prelude::*;
+use storage_proofs_core::{
+    merkle::{get_base_tree_count, MerkleTreeTrait},
+    util::{default_rows_to_discard, NODE_SIZE},
+};
 use typenum::Unsigned;
 
-use crate::types::{Commitment, SectorSize};
+use crate::{
+    constants::PUBLISHED_SECTOR_SIZES,
+    types::{
+        Commitment, HasherKind, PoRepConfig, SectorArtifactSizes, SectorSize, TreeArities,
+        TreeArity, BINARY_ARITY,
+    },
+    with_shape,
+};
 
 pub fn as_safe_commitment<H: Domain, T: AsRef<str>>(
     comm: &[u8; 32],
@@ -19,6 +39,21 @@ pub fn as_safe_commitment<H: Domain, T: AsRef<str>>(
         .with_context(|| format!("Invalid commitment ({})", commitment_name.as_ref(),))
 }
 
+/// Converts a byte slice, e.g. one received over FFI, into a `Commitment`,
+/// erroring instead of panicking when it is not exactly 32 bytes long.
+pub fn commitment_from_slice(bytes: &[u8]) -> Result<Commitment> {
+    let mut commitment = [0; 32];
+    ensure!(
+        bytes.len() == commitment.len(),
+        "invalid commitment length {} (expected {} bytes)",
+        bytes.len(),
+        commitment.len(),
+    );
+    commitment.copy_from_slice(bytes);
+
+    Ok(commitment)
+}
+
 pub fn commitment_from_fr(fr: Fr) -> Commitment {
     let mut commitment = [0; 32];
     for (i, b) in fr_into_bytes(&fr).iter().enumerate() {
@@ -27,6 +62,228 @@ pub fn commitment_from_fr(fr: Fr) -> Commitment {
     commitment
 }
 
+/// Infers the sector size of the replica at `replica_path` from its length,
+/// erroring if the length is not one of the supported sector sizes.
+pub fn infer_sector_size<T: AsRef<Path>>(replica_path: T) -> Result<SectorSize> {
+    let replica_len = metadata(replica_path.as_ref())
+        .with_context(|| {
+            format!(
+                "could not read metadata of replica={:?}",
+                replica_path.as_ref()
+            )
+        })?
+        .len();
+    ensure!(
+        PUBLISHED_SECTOR_SIZES.contains(&replica_len),
+        "replica length {} is not a supported sector size (supported sector sizes: {:?})",
+        replica_len,
+        PUBLISHED_SECTOR_SIZES,
+    );
+
+    Ok(SectorSize(replica_len))
+}
+
+/// Returns the node ranges `(start, end)`, with `end` exclusive, of all runs of
+/// at least `min_run_nodes` consecutive all-zero nodes in the file at `path`.
+///
+/// A replica should contain no large zero runs, so this is useful for spotting
+/// truncated or partially written files.
+pub fn find_zero_runs<T: AsRef<Path>>(
+    path: T,
+    min_run_nodes: usize,
+) -> Result<Vec<(usize, usize)>> {
+    // Number of nodes scanned per parallel chunk.
+    const CHUNK_NODES: usize = 1 << 16;
+
+    ensure!(min_run_nodes > 0, "min_run_nodes must be non-zero");
+
+    let file = File::open(path.as_ref())
+        .with_context(|| format!("could not open file={:?}", path.as_ref()))?;
+    let file_len = file.metadata()?.len() as usize;
+    ensure!(
+        file_len % NODE_SIZE == 0,
+        "file length {} is not a multiple of the node size {}",
+        file_len,
+        NODE_SIZE,
+    );
+    if file_len == 0 {
+        return Ok(Vec::new());
+    }
+
+    let data = unsafe { MmapOptions::new().map(&file) }
+        .with_context(|| format!("could not mmap file={:?}", path.as_ref()))?;
+
+    let chunk_runs: Vec<Vec<(usize, usize)>> = data
+        .par_chunks(CHUNK_NODES * NODE_SIZE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let first_node = i * CHUNK_NODES;
+            let mut runs = Vec::new();
+            let mut run_start = None;
+            for (j, node) in chunk.chunks(NODE_SIZE).enumerate() {
+                let is_zero = node.iter().all(|b| *b == 0);
+                match (is_zero, run_start) {
+                    (true, None) => run_start = Some(first_node + j),
+                    (false, Some(start)) => {
+                        runs.push((start, first_node + j));
+                        run_start = None;
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(start) = run_start {
+                runs.push((start, first_node + chunk.len() / NODE_SIZE));
+            }
+            runs
+        })
+        .collect();
+
+    // Join runs spanning chunk boundaries before applying the minimum length.
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for run in chunk_runs.into_iter().flatten() {
+        if let Some(last) = runs.last_mut() {
+            if last.1 == run.0 {
+                last.1 = run.1;
+                continue;
+            }
+        }
+        runs.push(run);
+    }
+    runs.retain(|(start, end)| end - start >= min_run_nodes);
+
+    Ok(runs)
+}
+
+/// Computes the root of the merkle tree with the given `hasher` and `arity`
+/// over the nodes of the file at `data_path`, reading the file once.
+///
+/// The nodes are used as the tree's leaves as they are, so for Poseidon every
+/// node must be a valid field element.
+///
+/// # Arguments
+///
+/// * `data_path` - path to the data, a power of `arity` number of nodes long.
+/// * `hasher` - the hash function used for the tree.
+/// * `arity` - the arity of the tree; 2, 4, 8 or 16 for Poseidon, and any power of two otherwise.
+pub fn merkle_root<T: AsRef<Path>>(
+    data_path: T,
+    hasher: HasherKind,
+    arity: usize,
+) -> Result<Commitment> {
+    ensure!(
+        arity >= 2 && arity.is_power_of_two(),
+        "unsupported arity {}",
+        arity
+    );
+
+    match hasher {
+        HasherKind::Poseidon => {
+            ensure!(arity <= 16, "unsupported arity {} for poseidon", arity);
+            merkle_root_with::<PoseidonHasher, _>(data_path, arity, true)
+        }
+        HasherKind::Sha256 => merkle_root_with::<Sha256Hasher, _>(data_path, arity, false),
+        HasherKind::Blake2s => merkle_root_with::<Blake2sHasher, _>(data_path, arity, false),
+    }
+}
+
+fn merkle_root_with<H: Hasher, T: AsRef<Path>>(
+    data_path: T,
+    arity: usize,
+    field_leafs: bool,
+) -> Result<Commitment> {
+    let data_len = metadata(data_path.as_ref())
+        .with_context(|| format!("could not read metadata of file={:?}", data_path.as_ref()))?
+        .len() as usize;
+    ensure!(
+        data_len % NODE_SIZE == 0,
+        "file length {} is not a multiple of the node size {}",
+        data_len,
+        NODE_SIZE,
+    );
+
+    let leafs = data_len / NODE_SIZE;
+    ensure!(
+        leafs.is_power_of_two(),
+        "number of leafs {} is not a power of two",
+        leafs
+    );
+    let mut height = 0;
+    let mut width = leafs;
+    while width > 1 && width % arity == 0 {
+        width /= arity;
+        height += 1;
+    }
+    ensure!(
+        height > 0 && width == 1,
+        "number of leafs {} is not a power of the arity {}",
+        leafs,
+        arity,
+    );
+
+    let file = File::open(data_path.as_ref())
+        .with_context(|| format!("could not open file={:?}", data_path.as_ref()))?;
+    let root = stream_merkle_root::<H, _>(
+        BufReader::new(file),
+        leafs,
+        &vec![arity; height],
+        field_leafs,
+    )?;
+
+    let mut commitment = [0; 32];
+    commitment.copy_from_slice(root.as_ref());
+
+    Ok(commitment)
+}
+
+/// Computes the root of the tree over the next `leafs` nodes read from
+/// `reader`, where `arities` holds the arity of each tree level from the leaves
+/// up. Only the incomplete node group of each level is kept in memory.
+///
+/// If `field_leafs` is set, every leaf must be a valid field element, as
+/// required by Poseidon.
+pub(crate) fn stream_merkle_root<H: Hasher, R: Read>(
+    mut reader: R,
+    leafs: usize,
+    arities: &[usize],
+    field_leafs: bool,
+) -> Result<H::Domain> {
+    let mut hasher = H::Function::default();
+    let mut pending: Vec<Vec<H::Domain>> = arities
+        .iter()
+        .map(|arity| Vec::with_capacity(*arity))
+        .collect();
+    let mut root = None;
+    let mut buf = [0u8; NODE_SIZE];
+
+    for i in 0..leafs {
+        reader
+            .read_exact(&mut buf)
+            .with_context(|| format!("could not read node {}", i))?;
+        if field_leafs {
+            bytes_into_fr(&buf)
+                .with_context(|| format!("node {} is not a valid field element", i))?;
+        }
+        let mut node = H::Domain::try_from_bytes(&buf)?;
+
+        // Carry the node up through every level it completes.
+        for (height, (pending, arity)) in pending.iter_mut().zip(arities).enumerate() {
+            pending.push(node);
+            if pending.len() < *arity {
+                break;
+            }
+            hasher.reset();
+            node = hasher.multi_node(pending, height);
+            pending.clear();
+
+            if height == arities.len() - 1 {
+                root = Some(node);
+            }
+        }
+    }
+
+    root.context("data did not produce a merkle root")
+}
+
 pub fn get_base_tree_size<Tree: MerkleTreeTrait>(sector_size: SectorSize) -> Result<usize> {
     let base_tree_leaves = u64::from(sector_size) as usize
         / size_of::<<Tree::Hasher as Hasher>::Domain>()
@@ -38,3 +295,71 @@ pub fn get_base_tree_size<Tree: MerkleTreeTrait>(sector_size: SectorSize) -> Res
 pub fn get_base_tree_leafs<Tree: MerkleTreeTrait>(base_tree_size: usize) -> Result<usize> {
     get_merkle_tree_leafs(base_tree_size, Tree::Arity::to_usize())
 }
+
+/// Returns the expected on-disk sizes of the replica, tree_d, tree_c and
+/// tree_r_last stores for a sector of the given config, where tree_c and
+/// tree_r_last sizes are summed over all of their base tree files. The
+/// tree_r_last size assumes the default `rows_to_discard`.
+pub fn sector_artifact_sizes(porep_config: PoRepConfig) -> Result<SectorArtifactSizes> {
+    porep_config.validate_supported()?;
+
+    with_shape!(
+        u64::from(porep_config.sector_size),
+        sector_artifact_sizes_for_shape,
+        porep_config
+    )
+}
+
+fn sector_artifact_sizes_for_shape<Tree: MerkleTreeTrait>(
+    porep_config: PoRepConfig,
+) -> Result<SectorArtifactSizes> {
+    let sector_size = u64::from(porep_config.sector_size);
+    let nodes_count = porep_config.nodes_count()?;
+    let base_tree_count = get_base_tree_count::<Tree>();
+    let base_tree_leafs = nodes_count / base_tree_count;
+    let arity = Tree::Arity::to_usize();
+
+    let tree_d_len = get_merkle_tree_len(nodes_count, BINARY_ARITY)?;
+    let tree_c_len = get_merkle_tree_len(base_tree_leafs, arity)?;
+    let tree_r_last_len = get_merkle_tree_cache_size(
+        base_tree_leafs,
+        arity,
+        default_rows_to_discard(base_tree_leafs, arity),
+    )?;
+
+    Ok(SectorArtifactSizes {
+        replica: sector_size,
+        tree_d: (tree_d_len * NODE_SIZE) as u64,
+        tree_c: (base_tree_count * tree_c_len * NODE_SIZE) as u64,
+        tree_r_last: (base_tree_count * tree_r_last_len * NODE_SIZE) as u64,
+    })
+}
+
+/// Returns the arities of the trees built for a sector of the given config.
+/// tree_d is always binary; tree_c and tree_r_last share the sector shape.
+pub fn tree_arities(porep_config: PoRepConfig) -> Result<TreeArities> {
+    porep_config.validate_supported()?;
+
+    Ok(with_shape!(
+        u64::from(porep_config.sector_size),
+        tree_arities_for_shape
+    ))
+}
+
+fn tree_arities_for_shape<Tree: MerkleTreeTrait>() -> TreeArities {
+    let shape = TreeArity {
+        base: Tree::Arity::to_usize(),
+        sub: Tree::SubTreeArity::to_usize(),
+        top: Tree::TopTreeArity::to_usize(),
+    };
+
+    TreeArities {
+        d: TreeArity {
+            base: BINARY_ARITY,
+            sub: 0,
+            top: 0,
+        },
+        c: shape,
+        r: shape,
+    }
+}
@@ -9,11 +9,15 @@ use crate::{constants::DefaultPieceHasher, pieces::piece_hash};
 
 /// Calculates comm-d of the data piped through to it.
 /// Data must be bit padded and power of 2 bytes.
+///
+/// All-zero subtrees are not hashed, their precomputed roots are used instead,
+/// which makes mostly-zero data cheap to commit to.
 pub struct CommitmentReader<R> {
     source: R,
     buffer: [u8; 64],
     buffer_pos: usize,
     current_tree: Vec<<DefaultPieceHasher as Hasher>::Domain>,
+    zero_leaf_hash: <DefaultPieceHasher as Hasher>::Domain,
 }
 
 impl<R: Read> CommitmentReader<R> {
@@ -23,6 +27,7 @@ impl<R: Read> CommitmentReader<R> {
             buffer: [0u8; 64],
             buffer_pos: 0,
             current_tree: Vec::new(),
+            zero_leaf_hash: <DefaultPieceHasher as Hasher>::Function::hash(&[0u8; 64]),
         }
     }
 
@@ -33,7 +38,11 @@ impl<R: Read> CommitmentReader<R> {
         }
 
         // WARNING: keep in sync with DefaultPieceHasher and its .node impl
-        let hash = <DefaultPieceHasher as Hasher>::Function::hash(&self.buffer);
+        let hash = if self.buffer.iter().all(|b| *b == 0) {
+            self.zero_leaf_hash
+        } else {
+            <DefaultPieceHasher as Hasher>::Function::hash(&self.buffer)
+        };
         self.current_tree.push(hash);
         self.buffer_pos = 0;
 
@@ -43,17 +52,31 @@ impl<R: Read> CommitmentReader<R> {
     pub fn finish(self) -> Result<<DefaultPieceHasher as Hasher>::Domain> {
         ensure!(self.buffer_pos == 0, "not enough inputs provided");
 
-        let CommitmentReader { current_tree, .. } = self;
+        let CommitmentReader {
+            current_tree,
+            zero_leaf_hash,
+            ..
+        } = self;
 
         let mut current_row = current_tree;
+        // Root of an all-zero subtree at the height of `current_row`.
+        let mut zero_root = zero_leaf_hash;
 
         while current_row.len() > 1 {
+            let next_zero_root = piece_hash(zero_root.as_ref(), zero_root.as_ref());
             let next_row = current_row
                 .par_chunks(2)
-                .map(|chunk| piece_hash(chunk[0].as_ref(), chunk[1].as_ref()))
+                .map(|chunk| {
+                    if chunk[0] == zero_root && chunk[1] == zero_root {
+                        next_zero_root
+                    } else {
+                        piece_hash(chunk[0].as_ref(), chunk[1].as_ref())
+                    }
+                })
                 .collect::<Vec<_>>();
 
             current_row = next_row;
+            zero_root = next_zero_root;
         }
         debug_assert_eq!(current_row.len(), 1);
 
@@ -116,4 +139,30 @@ mod tests {
 
         assert_eq!(&commitment1[..], AsRef::<[u8]>::as_ref(&commitment2));
     }
+
+    #[test]
+    fn test_commitment_reader_sparse() {
+        let piece_size = 127 * 64;
+        let mut source = vec![0u8; piece_size];
+        source[127 * 5..127 * 5 + 40].copy_from_slice(&[255u8; 40]);
+        source[piece_size - 1] = 1;
+
+        let padded_size = PaddedBytesAmount::from(UnpaddedBytesAmount(piece_size as u64));
+        for source in &[vec![0u8; piece_size], source] {
+            let mut fr32_reader = Fr32Reader::new(Cursor::new(source));
+            let commitment1 = generate_piece_commitment_bytes_from_source::<DefaultPieceHasher>(
+                &mut fr32_reader,
+                padded_size.into(),
+            )
+            .expect("failed to generate piece commitment bytes from source");
+
+            let fr32_reader = Fr32Reader::new(Cursor::new(source));
+            let mut commitment_reader = CommitmentReader::new(fr32_reader);
+            io::copy(&mut commitment_reader, &mut io::sink()).expect("io copy failed");
+
+            let commitment2 = commitment_reader.finish().expect("failed to finish");
+
+            assert_eq!(&commitment1[..], AsRef::<[u8]>::as_ref(&commitment2));
+        }
+    }
 }
@@ -12,8 +12,10 @@ pub mod types;
 
 mod api;
 mod commitment_reader;
+mod piece_writer;
 
 pub use api::*;
 pub use commitment_reader::*;
 pub use constants::*;
+pub use piece_writer::*;
 pub use types::*;
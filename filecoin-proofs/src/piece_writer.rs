@@ -0,0 +1,232 @@
+use std::cmp::min;
+use std::io::{self, Read, Write};
+
+use anyhow::{ensure, Context, Result};
+use filecoin_hashers::{HashFunction, Hasher};
+use fr32::Fr32Reader;
+
+use crate::{
+    api::ensure_piece_size,
+    constants::DefaultPieceHasher,
+    pieces::{get_piece_alignment, piece_hash, sum_piece_bytes_with_alignment},
+    types::{PaddedBytesAmount, PieceInfo, UnpaddedBytesAmount},
+};
+
+/// Number of unpadded bytes which fr32 padding turns into `PADDED_CHUNK_SIZE` bytes.
+const UNPADDED_CHUNK_SIZE: usize = 127;
+const PADDED_CHUNK_SIZE: usize = 128;
+
+/// Writes a piece to `target` exactly like `add_piece`, but as a `Write` sink:
+/// bytes written to it are bit padded and written through to `target`, and
+/// comm_p is computed on the fly, so piece data only has to be passed once.
+///
+/// Only the roots of completed subtrees are kept, one per tree level.
+pub struct PieceWriter<W: Write> {
+    target: W,
+    piece_size: UnpaddedBytesAmount,
+    right_alignment: UnpaddedBytesAmount,
+    written: UnpaddedBytesAmount,
+    bytes_accepted: u64,
+    buffer: [u8; UNPADDED_CHUNK_SIZE],
+    buffer_pos: usize,
+    levels: Vec<Option<<DefaultPieceHasher as Hasher>::Domain>>,
+}
+
+impl<W: Write> PieceWriter<W> {
+    /// Creates a writer for a piece of `piece_size` unpadded bytes, writing the
+    /// left alignment required after the previous pieces in `piece_lengths`.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - a writer where the processed piece bytes are written.
+    /// * `piece_size` - the number of unpadded user-bytes which will be written.
+    /// * `piece_lengths` - the number of bytes for each previous piece in the sector.
+    pub fn new(
+        mut target: W,
+        piece_size: UnpaddedBytesAmount,
+        piece_lengths: &[UnpaddedBytesAmount],
+    ) -> Result<Self> {
+        ensure_piece_size(piece_size)?;
+
+        let written_bytes = sum_piece_bytes_with_alignment(piece_lengths);
+        let piece_alignment = get_piece_alignment(written_bytes, piece_size);
+
+        write_zeroes(&mut target, piece_alignment.left_bytes)
+            .context("failed to write left alignment")?;
+
+        Ok(PieceWriter {
+            target,
+            piece_size,
+            right_alignment: piece_alignment.right_bytes,
+            written: piece_alignment.sum(piece_size),
+            bytes_accepted: 0,
+            buffer: [0u8; UNPADDED_CHUNK_SIZE],
+            buffer_pos: 0,
+            levels: Vec::new(),
+        })
+    }
+
+    /// Writes the right alignment and returns the piece info together with the
+    /// number of bytes written to `target` (piece plus alignment), as `add_piece` does.
+    pub fn finish(mut self) -> Result<(PieceInfo, UnpaddedBytesAmount)> {
+        ensure!(
+            self.bytes_accepted == u64::from(self.piece_size),
+            "PieceWriter: invalid bytes amount written ({} of {})",
+            self.bytes_accepted,
+            u64::from(self.piece_size),
+        );
+        debug_assert_eq!(self.buffer_pos, 0);
+
+        write_zeroes(&mut self.target, self.right_alignment)
+            .context("failed to write right alignment")?;
+        self.target.flush()?;
+
+        let root = self.levels.pop().flatten();
+        ensure!(
+            self.levels.iter().all(Option::is_none),
+            "PieceWriter: incomplete piece tree"
+        );
+        let root = root.context("PieceWriter: no piece data written")?;
+
+        let mut comm = [0u8; 32];
+        comm.copy_from_slice(root.as_ref());
+
+        Ok((PieceInfo::new(comm, self.piece_size)?, self.written))
+    }
+
+    /// Pads the full buffer, writes it through and hashes it into the tree.
+    fn write_chunk(&mut self) -> io::Result<()> {
+        let mut padded = [0u8; PADDED_CHUNK_SIZE];
+        Fr32Reader::new(&self.buffer[..]).read_exact(&mut padded)?;
+        self.target.write_all(&padded)?;
+        self.buffer_pos = 0;
+
+        // WARNING: keep in sync with DefaultPieceHasher and its .node impl
+        for leaves in padded.chunks(64) {
+            let hash = <DefaultPieceHasher as Hasher>::Function::hash(leaves);
+            self.push_hash(hash);
+        }
+
+        Ok(())
+    }
+
+    /// Adds a leaf hash, combining it with completed siblings up the tree.
+    fn push_hash(&mut self, mut hash: <DefaultPieceHasher as Hasher>::Domain) {
+        for level in self.levels.iter_mut() {
+            match level.take() {
+                Some(left) => hash = piece_hash(left.as_ref(), hash.as_ref()),
+                None => {
+                    *level = Some(hash);
+                    return;
+                }
+            }
+        }
+        self.levels.push(Some(hash));
+    }
+}
+
+impl<W: Write> Write for PieceWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let remaining = u64::from(self.piece_size) - self.bytes_accepted;
+        if remaining == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "PieceWriter: piece size exceeded",
+            ));
+        }
+
+        let n = min(
+            min(buf.len(), UNPADDED_CHUNK_SIZE - self.buffer_pos),
+            remaining as usize,
+        );
+        self.buffer[self.buffer_pos..self.buffer_pos + n].copy_from_slice(&buf[..n]);
+        self.buffer_pos += n;
+        self.bytes_accepted += n as u64;
+
+        if self.buffer_pos == UNPADDED_CHUNK_SIZE {
+            self.write_chunk()?;
+        }
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.target.flush()
+    }
+}
+
+fn write_zeroes<W: Write>(target: &mut W, unpadded: UnpaddedBytesAmount) -> io::Result<()> {
+    let padded = u64::from(PaddedBytesAmount::from(unpadded));
+    io::copy(&mut io::repeat(0).take(padded), target)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    use crate::api::add_piece;
+
+    #[test]
+    fn test_piece_writer_matches_add_piece() {
+        let rng = &mut XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let mut piece_lengths = Vec::new();
+        let mut expected_sector = Vec::new();
+        let mut sector = Vec::new();
+
+        for piece_size in &[127 * 8, 127 * 4, 127 * 16] {
+            let piece_size = UnpaddedBytesAmount(*piece_size);
+            let data: Vec<u8> = (0..u64::from(piece_size)).map(|_| rng.gen()).collect();
+
+            let expected = add_piece(
+                Cursor::new(&data),
+                &mut expected_sector,
+                piece_size,
+                &piece_lengths,
+            )
+            .expect("add_piece failed");
+
+            let mut writer =
+                PieceWriter::new(&mut sector, piece_size, &piece_lengths).expect("new failed");
+            // Write in uneven chunks to cross the 127 byte padding boundaries.
+            for chunk in data.chunks(50) {
+                writer.write_all(chunk).expect("write failed");
+            }
+            let actual = writer.finish().expect("finish failed");
+
+            assert_eq!(actual.0, expected.0);
+            assert_eq!(actual.1, expected.1);
+            assert_eq!(sector, expected_sector);
+
+            piece_lengths.push(piece_size);
+        }
+    }
+
+    #[test]
+    fn test_piece_writer_size_mismatch() {
+        let piece_size = UnpaddedBytesAmount(127 * 8);
+
+        let mut writer = PieceWriter::new(io::sink(), piece_size, &[]).expect("new failed");
+        writer.write_all(&[1u8; 127]).expect("write failed");
+        assert!(writer.finish().is_err());
+
+        let mut writer = PieceWriter::new(io::sink(), piece_size, &[]).expect("new failed");
+        assert!(writer.write_all(&[1u8; 127 * 8 + 1]).is_err());
+
+        assert!(PieceWriter::new(io::sink(), UnpaddedBytesAmount(127 * 3), &[]).is_err());
+    }
+}
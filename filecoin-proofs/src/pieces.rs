@@ -1,18 +1,14 @@
 use std::cmp::min;
-use std::collections::HashMap;
 use std::io::{self, Cursor, Read};
 use std::iter::Iterator;
-use std::sync::Mutex;
 
 use anyhow::{ensure, Context, Result};
 use filecoin_hashers::{HashFunction, Hasher};
-use fr32::Fr32Reader;
-use lazy_static::lazy_static;
 use log::info;
 use storage_proofs_core::util::NODE_SIZE;
 
 use crate::{
-    commitment_reader::CommitmentReader,
+    api::ensure_piece_size,
     constants::{
         DefaultPieceHasher,
         MINIMUM_RESERVED_BYTES_FOR_PIECE_IN_FULLY_ALIGNED_SECTOR as MINIMUM_PIECE_SIZE,
@@ -24,6 +20,9 @@ use crate::{
 };
 
 /// Verify that the provided `piece_infos` and `comm_d` match.
+///
+/// An empty `piece_infos` describes a sector holding no pieces, which is treated as
+/// a single implicit zero piece covering the whole sector (see `compute_comm_d`).
 pub fn verify_pieces(
     comm_d: &Commitment,
     piece_infos: &[PieceInfo],
@@ -34,10 +33,6 @@ pub fn verify_pieces(
     Ok(&comm_d_calculated == comm_d)
 }
 
-lazy_static! {
-    static ref COMMITMENTS: Mutex<HashMap<SectorSize, Commitment>> = Mutex::new(HashMap::new());
-}
-
 #[derive(Debug, Clone)]
 pub struct EmptySource {
     size: usize,
@@ -61,31 +56,16 @@ impl Read for EmptySource {
     }
 }
 
-fn empty_comm_d(sector_size: SectorSize) -> Commitment {
-    let map = &mut *COMMITMENTS.lock().expect("COMMITMENTS poisoned");
-
-    *map.entry(sector_size).or_insert_with(|| {
-        let size: UnpaddedBytesAmount = sector_size.into();
-        let fr32_reader = Fr32Reader::new(EmptySource::new(size.into()));
-        let mut commitment_reader = CommitmentReader::new(fr32_reader);
-        io::copy(&mut commitment_reader, &mut io::sink())
-            .expect("failed to copy commitment to sink");
-
-        let mut comm = [0u8; 32];
-        comm.copy_from_slice(
-            commitment_reader
-                .finish()
-                .expect("failed to create commitment")
-                .as_ref(),
-        );
-        comm
-    })
-}
-
+/// Computes `comm_d` for a sector with the given pieces, laid out in order and padded with
+/// zero pieces as needed.
+///
+/// If `piece_infos` is empty, the result is the `comm_d` of an all-zero sector, i.e. the same
+/// value as for a single zero piece covering the whole sector, consistent with unsealed data
+/// being zero-padded to the sector size when sealing.
 pub fn compute_comm_d(sector_size: SectorSize, piece_infos: &[PieceInfo]) -> Result<Commitment> {
     info!("verifying {} pieces", piece_infos.len());
     if piece_infos.is_empty() {
-        return Ok(empty_comm_d(sector_size));
+        return Ok(zero_padding(sector_size.into())?.commitment);
     }
 
     let unpadded_sector: UnpaddedBytesAmount = sector_size.into();
@@ -284,6 +264,38 @@ pub fn get_piece_start_byte(
     UnpaddedByteIndex::from(last_byte + alignment.left_bytes)
 }
 
+/// Given a list of pieces placed in a sector in that order, return the range of nodes
+/// `[start, end)` occupied by the piece at `index`, alignment excluded.
+///
+/// # Arguments
+///
+/// * `piece_infos` - the pieces of the sector, as passed to seal.
+/// * `index` - the index of the target piece in `piece_infos`.
+pub fn piece_node_range(piece_infos: &[PieceInfo], index: usize) -> Result<(usize, usize)> {
+    ensure!(
+        index < piece_infos.len(),
+        "piece index {} out of range for {} pieces",
+        index,
+        piece_infos.len()
+    );
+
+    let piece_lengths: Vec<_> = piece_infos[..=index].iter().map(|p| p.size).collect();
+    for piece_size in &piece_lengths {
+        ensure_piece_size(*piece_size)?;
+    }
+
+    let piece_size = piece_lengths[index];
+    let written_bytes = sum_piece_bytes_with_alignment(&piece_lengths[..index]);
+    let alignment = get_piece_alignment(written_bytes, piece_size);
+
+    let start = usize::from(PaddedBytesAmount::from(
+        written_bytes + alignment.left_bytes,
+    ));
+    let len = usize::from(PaddedBytesAmount::from(piece_size));
+
+    Ok((start / NODE_SIZE, (start + len) / NODE_SIZE))
+}
+
 /// Given a number of bytes already written to a staged sector (ignoring bit padding) and a number
 /// of bytes (before bit padding) to be added, return the alignment required to create a piece where
 /// len(piece) == len(sector size)/(2^n) and sufficient left padding to ensure simple merkle proof
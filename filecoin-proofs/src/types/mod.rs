@@ -50,6 +50,78 @@ pub struct SealPreCommitOutput {
     pub comm_d: Commitment,
 }
 
+/// Expected on-disk sizes, in bytes, of a sector's replica and persisted tree stores.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectorArtifactSizes {
+    pub replica: u64,
+    pub tree_d: u64,
+    pub tree_c: u64,
+    pub tree_r_last: u64,
+}
+
+/// Base, sub and top tree arities of a merkle tree shape. The sub and top
+/// arities are 0 for trees without those levels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreeArity {
+    pub base: usize,
+    pub sub: usize,
+    pub top: usize,
+}
+
+/// Arities of the tree_d, tree_c and tree_r_last trees of a sector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreeArities {
+    pub d: TreeArity,
+    pub c: TreeArity,
+    pub r: TreeArity,
+}
+
+/// Hash functions with which `merkle_root` can build a tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HasherKind {
+    Poseidon,
+    Sha256,
+    Blake2s,
+}
+
+/// Summary of the `StoreConfig` of a single store referenced by `t_aux`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoreConfigDescription {
+    pub id: String,
+    pub size: Option<usize>,
+    pub rows_to_discard: usize,
+}
+
+impl From<&StoreConfig> for StoreConfigDescription {
+    fn from(config: &StoreConfig) -> Self {
+        StoreConfigDescription {
+            id: config.id.clone(),
+            size: config.size,
+            rows_to_discard: config.rows_to_discard,
+        }
+    }
+}
+
+/// Summary of the store configs persisted in a sector's `t_aux`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TAuxDescription {
+    pub labels: Vec<StoreConfigDescription>,
+    pub tree_d: StoreConfigDescription,
+    pub tree_c: StoreConfigDescription,
+    pub tree_r_last: StoreConfigDescription,
+}
+
+impl<Tree: MerkleTreeTrait> From<&TemporaryAux<Tree, DefaultPieceHasher>> for TAuxDescription {
+    fn from(t_aux: &TemporaryAux<Tree, DefaultPieceHasher>) -> Self {
+        TAuxDescription {
+            labels: t_aux.labels.labels.iter().map(Into::into).collect(),
+            tree_d: (&t_aux.tree_d_config).into(),
+            tree_c: (&t_aux.tree_c_config).into(),
+            tree_r_last: (&t_aux.tree_r_last_config).into(),
+        }
+    }
+}
+
 pub type VanillaSealProof<Tree> = stacked::Proof<Tree, DefaultPieceHasher>;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1,10 +1,19 @@
 use std::fmt::{self, Debug, Formatter};
 
-use anyhow::{ensure, Result};
+use anyhow::{ensure, Context, Result};
 use serde::{Deserialize, Serialize};
 
 use crate::types::{Commitment, UnpaddedBytesAmount};
 
+/// CIDv1 prefix of a piece CID: version 1, codec `fil-commitment-unsealed`
+/// (0xf101) and multihash `sha2-256-trunc254-padded` (0x1012) of 32 bytes,
+/// each as unsigned varints.
+const PIECE_CID_PREFIX: [u8; 7] = [0x01, 0x81, 0xe2, 0x03, 0x92, 0x20, 0x20];
+
+/// Multibase prefix for lowercase, unpadded RFC 4648 base32.
+const BASE32_MULTIBASE_PREFIX: char = 'b';
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
 #[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PieceInfo {
     pub commitment: Commitment,
@@ -25,4 +34,68 @@ impl PieceInfo {
         ensure!(commitment != [0; 32], "Invalid all zero commitment");
         Ok(PieceInfo { commitment, size })
     }
+
+    /// Returns the piece CID of this piece's commitment (`comm_p`), in the
+    /// base32 string form used on-chain and by markets.
+    pub fn to_piece_cid(&self) -> String {
+        let mut bytes = PIECE_CID_PREFIX.to_vec();
+        bytes.extend_from_slice(&self.commitment);
+
+        let mut cid = String::with_capacity(1 + (bytes.len() * 8 + 4) / 5);
+        cid.push(BASE32_MULTIBASE_PREFIX);
+        for chunk in bytes.chunks(5) {
+            let mut buf = [0u8; 5];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            let bits = buf.iter().fold(0u64, |acc, b| (acc << 8) | u64::from(*b));
+            for i in 0..(chunk.len() * 8 + 4) / 5 {
+                cid.push(BASE32_ALPHABET[((bits >> (35 - 5 * i)) & 0x1f) as usize] as char);
+            }
+        }
+
+        cid
+    }
+
+    /// Creates a `PieceInfo` from a piece CID as returned by `to_piece_cid`.
+    ///
+    /// # Arguments
+    ///
+    /// * `cid` - the base32 piece CID.
+    /// * `size` - the number of unpadded bytes in the piece.
+    pub fn from_piece_cid(cid: &str, size: UnpaddedBytesAmount) -> Result<Self> {
+        let encoded = cid
+            .strip_prefix(BASE32_MULTIBASE_PREFIX)
+            .with_context(|| format!("piece CID {} is not base32 encoded", cid))?;
+
+        let mut bytes = Vec::with_capacity(encoded.len() * 5 / 8);
+        let mut bits = 0u64;
+        let mut bit_count = 0;
+        for c in encoded.bytes() {
+            let value = BASE32_ALPHABET
+                .iter()
+                .position(|a| *a == c)
+                .with_context(|| format!("invalid character in piece CID {}", cid))?;
+            bits = (bits << 5) | value as u64;
+            bit_count += 5;
+            if bit_count >= 8 {
+                bit_count -= 8;
+                bytes.push((bits >> bit_count) as u8);
+            }
+        }
+        ensure!(
+            bits & ((1 << bit_count) - 1) == 0,
+            "invalid trailing bits in piece CID {}",
+            cid
+        );
+
+        ensure!(
+            bytes.len() == PIECE_CID_PREFIX.len() + 32 && bytes.starts_with(&PIECE_CID_PREFIX),
+            "{} is not a piece CID",
+            cid
+        );
+
+        let mut commitment = [0u8; 32];
+        commitment.copy_from_slice(&bytes[PIECE_CID_PREFIX.len()..]);
+
+        PieceInfo::new(commitment, size)
+    }
 }
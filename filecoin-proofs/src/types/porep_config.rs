@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use storage_proofs_core::{
     api_version::ApiVersion,
     merkle::MerkleTreeTrait,
@@ -8,11 +8,15 @@ use storage_proofs_core::{
         parameter_cache_metadata_path, parameter_cache_params_path,
         parameter_cache_verifying_key_path, CacheableParameters,
     },
+    util::NODE_SIZE,
 };
 use storage_proofs_porep::stacked::{StackedCircuit, StackedCompound};
 
 use crate::{
-    constants::DefaultPieceHasher,
+    constants::{
+        DefaultPieceHasher, LAYERS, POREP_MINIMUM_CHALLENGES, POREP_PARTITIONS,
+        PUBLISHED_SECTOR_SIZES,
+    },
     parameters::public_params,
     types::{PaddedBytesAmount, PoRepProofPartitions, SectorSize, UnpaddedBytesAmount},
 };
@@ -54,6 +58,76 @@ impl From<PoRepConfig> for SectorSize {
 }
 
 impl PoRepConfig {
+    /// Returns the number of nodes in a sector of this config, erroring if
+    /// `sector_size` is not a whole number of nodes or the node count is not
+    /// a power of two.
+    pub fn nodes_count(&self) -> Result<usize> {
+        let sector_size = u64::from(self.sector_size);
+
+        ensure!(
+            sector_size % NODE_SIZE as u64 == 0,
+            "sector size {} is not a multiple of the node size {}",
+            sector_size,
+            NODE_SIZE,
+        );
+        let nodes_count = (sector_size / NODE_SIZE as u64) as usize;
+        ensure!(
+            nodes_count.is_power_of_two(),
+            "sector size {} does not have a power of two node count ({})",
+            sector_size,
+            nodes_count,
+        );
+
+        Ok(nodes_count)
+    }
+
+    /// Checks that `sector_size` has a valid node count (see `nodes_count`), is one
+    /// of the supported sector sizes and that the partition, layer and challenge
+    /// tables all have an entry for it, so that an unsupported config is rejected
+    /// before any work is done.
+    pub fn validate_supported(&self) -> Result<()> {
+        let sector_size = u64::from(self.sector_size);
+
+        self.nodes_count()?;
+
+        ensure!(
+            PUBLISHED_SECTOR_SIZES.contains(&sector_size),
+            "unsupported sector size {} (supported sector sizes: {:?})",
+            sector_size,
+            PUBLISHED_SECTOR_SIZES,
+        );
+        ensure!(
+            POREP_PARTITIONS
+                .read()
+                .expect("POREP_PARTITIONS poisoned")
+                .contains_key(&sector_size),
+            "no porep partition count configured for sector size {}",
+            sector_size,
+        );
+        ensure!(
+            LAYERS
+                .read()
+                .expect("LAYERS poisoned")
+                .contains_key(&sector_size),
+            "no layer count configured for sector size {}",
+            sector_size,
+        );
+        ensure!(
+            POREP_MINIMUM_CHALLENGES
+                .read()
+                .expect("POREP_MINIMUM_CHALLENGES poisoned")
+                .contains_key(&sector_size),
+            "no minimum challenge count configured for sector size {}",
+            sector_size,
+        );
+        ensure!(
+            usize::from(self.partitions) > 0,
+            "porep partition count must be non-zero"
+        );
+
+        Ok(())
+    }
+
     /// Returns the cache identifier as used by `storage-proofs::paramater_cache`.
     pub fn get_cache_identifier<Tree: 'static + MerkleTreeTrait>(&self) -> Result<String> {
         let params = public_params::<Tree>(
@@ -86,3 +160,68 @@ impl PoRepConfig {
         Ok(parameter_cache_params_path(&id))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::constants::{SECTOR_SIZE_2_KIB, SECTOR_SIZE_32_GIB};
+
+    fn porep_config(sector_size: u64, partitions: u8) -> PoRepConfig {
+        PoRepConfig {
+            sector_size: SectorSize(sector_size),
+            partitions: PoRepProofPartitions(partitions),
+            porep_id: [0; 32],
+            api_version: ApiVersion::V1_1_0,
+        }
+    }
+
+    #[test]
+    fn test_validate_supported() {
+        for sector_size in PUBLISHED_SECTOR_SIZES.iter() {
+            let partitions = *POREP_PARTITIONS
+                .read()
+                .expect("POREP_PARTITIONS poisoned")
+                .get(sector_size)
+                .expect("unknown sector size");
+            porep_config(*sector_size, partitions)
+                .validate_supported()
+                .expect("published sector size must be supported");
+        }
+
+        let err = porep_config(SECTOR_SIZE_2_KIB * 4, 1)
+            .validate_supported()
+            .expect_err("sector size is not supported");
+        assert!(format!("{}", err).contains("unsupported sector size 8192"));
+
+        assert!(porep_config(SECTOR_SIZE_32_GIB, 0)
+            .validate_supported()
+            .is_err());
+    }
+
+    #[test]
+    fn test_nodes_count() {
+        assert_eq!(
+            porep_config(SECTOR_SIZE_2_KIB, 1)
+                .nodes_count()
+                .expect("nodes_count failed"),
+            64
+        );
+        assert_eq!(
+            porep_config(SECTOR_SIZE_32_GIB, 10)
+                .nodes_count()
+                .expect("nodes_count failed"),
+            1 << 30
+        );
+
+        let err = porep_config(SECTOR_SIZE_2_KIB + 1, 1)
+            .nodes_count()
+            .expect_err("sector size is not a multiple of the node size");
+        assert!(format!("{}", err).contains("not a multiple of the node size"));
+
+        let err = porep_config(SECTOR_SIZE_2_KIB * 3, 1)
+            .validate_supported()
+            .expect_err("node count is not a power of two");
+        assert!(format!("{}", err).contains("power of two node count"));
+    }
+}
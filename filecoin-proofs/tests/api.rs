@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 use std::fs::{read_dir, remove_file};
 use std::io::{Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 use std::sync::Once;
 
@@ -9,26 +10,43 @@ use bellperson::groth16;
 use bincode::serialize;
 use blstrs::{Bls12, Scalar as Fr};
 use ff::Field;
-use filecoin_hashers::Hasher;
+use filecoin_hashers::{
+    blake2s::Blake2sHasher, poseidon::PoseidonHasher, sha256::Sha256Hasher, HashFunction, Hasher,
+    PoseidonArity,
+};
 use filecoin_proofs::{
-    add_piece, aggregate_seal_commit_proofs, clear_cache, compute_comm_d, fauxrep_aux,
-    generate_fallback_sector_challenges, generate_piece_commitment, generate_single_vanilla_proof,
-    generate_window_post, generate_window_post_with_vanilla, generate_winning_post,
-    generate_winning_post_sector_challenge, generate_winning_post_with_vanilla, get_seal_inputs,
+    add_piece, aggregate_seal_commit_proofs, assert_matching_comm_c, clear_cache,
+    commitment_from_fr, commitment_from_slice, compute_comm_d, describe_t_aux, fauxrep_aux,
+    find_zero_runs, generate_fallback_sector_challenges, generate_piece_commitment,
+    generate_single_vanilla_proof, generate_window_post, generate_window_post_with_vanilla,
+    generate_winning_post, generate_winning_post_sector_challenge,
+    generate_winning_post_with_vanilla, get_seal_inputs, infer_sector_size, merkle_root,
+    read_comm_r_last, rebuild_p_aux, rebuild_t_aux, replicas_equal_by_commitment,
     seal_commit_phase1, seal_commit_phase2, seal_pre_commit_phase1, seal_pre_commit_phase2,
-    unseal_range, validate_cache_for_commit, validate_cache_for_precommit_phase2,
-    verify_aggregate_seal_commit_proofs, verify_seal, verify_window_post, verify_winning_post,
-    Commitment, DefaultTreeDomain, MerkleTreeTrait, PaddedBytesAmount, PieceInfo, PoRepConfig,
+    sector_artifact_sizes, unseal_range, validate_cache_for_commit,
+    validate_cache_for_precommit_phase2, validate_t_aux_stores,
+    verify_aggregate_seal_commit_proofs, verify_comm_r_streaming, verify_seal, verify_window_post,
+    verify_winning_post, Commitment, DefaultPieceHasher, DefaultTreeDomain, DefaultTreeHasher,
+    HasherKind, Labels, MerkleTreeTrait, PaddedBytesAmount, PersistentAux, PieceInfo, PoRepConfig,
     PoRepProofPartitions, PoStConfig, PoStType, PrivateReplicaInfo, ProverId, PublicReplicaInfo,
-    SealCommitOutput, SealPreCommitOutput, SealPreCommitPhase1Output, SectorShape16KiB,
-    SectorShape2KiB, SectorShape32KiB, SectorShape4KiB, SectorSize, UnpaddedByteIndex,
-    UnpaddedBytesAmount, POREP_PARTITIONS, SECTOR_SIZE_16_KIB, SECTOR_SIZE_2_KIB,
-    SECTOR_SIZE_32_KIB, SECTOR_SIZE_4_KIB, WINDOW_POST_CHALLENGE_COUNT, WINDOW_POST_SECTOR_COUNT,
-    WINNING_POST_CHALLENGE_COUNT, WINNING_POST_SECTOR_COUNT,
+    SealCommitOutput, SealPreCommitOutput, SealPreCommitPhase1Output, SectorArtifactSizes,
+    SectorShape16KiB, SectorShape2KiB, SectorShape32KiB, SectorShape4KiB, SectorSize, StoreConfig,
+    StoreConfigDescription, TemporaryAux, UnpaddedByteIndex, UnpaddedBytesAmount, POREP_PARTITIONS,
+    SECTOR_SIZE_16_KIB, SECTOR_SIZE_2_KIB, SECTOR_SIZE_32_GIB, SECTOR_SIZE_32_KIB,
+    SECTOR_SIZE_4_KIB, SECTOR_SIZE_512_MIB, SECTOR_SIZE_8_MIB, WINDOW_POST_CHALLENGE_COUNT,
+    WINDOW_POST_SECTOR_COUNT, WINNING_POST_CHALLENGE_COUNT, WINNING_POST_SECTOR_COUNT,
 };
+use generic_array::typenum::{Unsigned, U0, U16, U2, U4, U8};
 use rand::{random, Rng, SeedableRng};
 use rand_xorshift::XorShiftRng;
-use storage_proofs_core::{api_version::ApiVersion, is_legacy_porep_id, sector::SectorId};
+use storage_proofs_core::{
+    api_version::ApiVersion,
+    cache_key::CacheKey,
+    is_legacy_porep_id,
+    merkle::{generate_tree, DiskTree},
+    sector::SectorId,
+    util::NODE_SIZE,
+};
 use tempfile::{tempdir, NamedTempFile, TempDir};
 
 // Use a fixed PoRep ID, so that the parents cache can be re-used between some tests.
@@ -1573,3 +1591,646 @@ fn test_aggregate_proof_encode_decode() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_commitment_from_slice() -> Result<()> {
+    let bytes: Vec<u8> = (0..33).collect();
+
+    let commitment = commitment_from_slice(&bytes[..32])?;
+    assert_eq!(&commitment[..], &bytes[..32]);
+
+    for len in &[0, 31, 33] {
+        let err = commitment_from_slice(&bytes[..*len]).expect_err("invalid length must fail");
+        assert!(format!("{}", err).contains(&format!("invalid commitment length {}", len)));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_find_zero_runs() -> Result<()> {
+    let nodes = (1 << 16) + 100;
+    let mut data = vec![1u8; nodes * NODE_SIZE];
+    // A run inside the first chunk, one crossing the first chunk boundary, one
+    // that is too short to be reported, and one reaching the end of the file.
+    for (start, end) in &[(10, 20), ((1 << 16) - 5, (1 << 16) + 5), (30, 32)] {
+        for b in &mut data[start * NODE_SIZE..end * NODE_SIZE] {
+            *b = 0;
+        }
+    }
+    for b in &mut data[(nodes - 10) * NODE_SIZE..] {
+        *b = 0;
+    }
+    // A node that is only partially zero is not part of a run.
+    data[15 * NODE_SIZE] = 1;
+
+    let mut file = NamedTempFile::new()?;
+    file.write_all(&data)?;
+    file.flush()?;
+
+    assert_eq!(
+        find_zero_runs(file.path(), 5)?,
+        vec![
+            (10, 15),
+            ((1 << 16) - 5, (1 << 16) + 5),
+            (nodes - 10, nodes)
+        ]
+    );
+    assert!(find_zero_runs(file.path(), 11)?.is_empty());
+    assert!(find_zero_runs(file.path(), 0).is_err());
+
+    file.as_file().set_len((nodes * NODE_SIZE + 1) as u64)?;
+    assert!(find_zero_runs(file.path(), 5).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_infer_sector_size() -> Result<()> {
+    let replica_file = NamedTempFile::new()?;
+
+    for sector_size in &[SECTOR_SIZE_2_KIB, SECTOR_SIZE_32_KIB, SECTOR_SIZE_8_MIB] {
+        replica_file.as_file().set_len(*sector_size)?;
+        assert_eq!(
+            infer_sector_size(replica_file.path())?,
+            SectorSize(*sector_size)
+        );
+    }
+
+    replica_file.as_file().set_len(SECTOR_SIZE_2_KIB + 1)?;
+    let err = infer_sector_size(replica_file.path()).expect_err("length is no sector size");
+    assert!(format!("{}", err).contains("is not a supported sector size"));
+
+    assert!(infer_sector_size(replica_file.path().with_extension("missing")).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_sector_artifact_sizes() -> Result<()> {
+    let porep_id = ARBITRARY_POREP_ID_V1_1_0;
+    let api_version = ApiVersion::V1_1_0;
+
+    let sizes = sector_artifact_sizes(porep_config(SECTOR_SIZE_2_KIB, porep_id, api_version))?;
+    assert_eq!(
+        sizes,
+        SectorArtifactSizes {
+            replica: 2048,
+            tree_d: 4064,
+            tree_c: 2336,
+            tree_r_last: 32,
+        }
+    );
+
+    // Compound shapes discard rows per base tree, not over the whole sector.
+    let sizes = sector_artifact_sizes(porep_config(SECTOR_SIZE_16_KIB, porep_id, api_version))?;
+    assert_eq!(
+        sizes,
+        SectorArtifactSizes {
+            replica: 16_384,
+            tree_d: 32_736,
+            tree_c: 18_688,
+            tree_r_last: 256,
+        }
+    );
+
+    let sizes = sector_artifact_sizes(porep_config(SECTOR_SIZE_32_KIB, porep_id, api_version))?;
+    assert_eq!(
+        sizes,
+        SectorArtifactSizes {
+            replica: 32_768,
+            tree_d: 65_504,
+            tree_c: 37_376,
+            tree_r_last: 512,
+        }
+    );
+
+    let sizes = sector_artifact_sizes(porep_config(SECTOR_SIZE_8_MIB, porep_id, api_version))?;
+    assert_eq!(
+        sizes,
+        SectorArtifactSizes {
+            replica: 8_388_608,
+            tree_d: 16_777_184,
+            tree_c: 9_586_976,
+            tree_r_last: 18_720,
+        }
+    );
+
+    let sizes = sector_artifact_sizes(porep_config(SECTOR_SIZE_512_MIB, porep_id, api_version))?;
+    assert_eq!(
+        sizes,
+        SectorArtifactSizes {
+            replica: 536_870_912,
+            tree_d: 1_073_741_792,
+            tree_c: 613_566_752,
+            tree_r_last: 1_198_368,
+        }
+    );
+
+    let sizes = sector_artifact_sizes(porep_config(SECTOR_SIZE_32_GIB, porep_id, api_version))?;
+    assert_eq!(
+        sizes,
+        SectorArtifactSizes {
+            replica: 34_359_738_368,
+            tree_d: 68_719_476_704,
+            tree_c: 39_268_272_384,
+            tree_r_last: 76_695_808,
+        }
+    );
+
+    let unsupported = PoRepConfig {
+        sector_size: SectorSize(SECTOR_SIZE_2_KIB * 4),
+        partitions: PoRepProofPartitions(1),
+        porep_id,
+        api_version,
+    };
+    assert!(sector_artifact_sizes(unsupported).is_err());
+
+    Ok(())
+}
+
+fn write_p_aux(cache_dir: &TempDir, p_aux: &PersistentAux<DefaultTreeDomain>) -> Result<()> {
+    let p_aux_path = cache_dir.path().join(CacheKey::PAux.to_string());
+    std::fs::write(&p_aux_path, serialize(p_aux)?)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_replicas_equal_by_commitment() -> Result<()> {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    let p_aux: PersistentAux<DefaultTreeDomain> = PersistentAux {
+        comm_c: Fr::random(&mut *rng).into(),
+        comm_r_last: Fr::random(&mut *rng).into(),
+    };
+    let other_p_aux = PersistentAux {
+        comm_c: p_aux.comm_c,
+        comm_r_last: Fr::random(&mut *rng).into(),
+    };
+
+    let cache_a = tempdir()?;
+    let cache_b = tempdir()?;
+    let cache_c = tempdir()?;
+    write_p_aux(&cache_a, &p_aux)?;
+    write_p_aux(&cache_b, &p_aux)?;
+    write_p_aux(&cache_c, &other_p_aux)?;
+
+    assert!(replicas_equal_by_commitment::<_, _, SectorShape2KiB>(
+        cache_a.path(),
+        cache_b.path()
+    )?);
+    assert!(!replicas_equal_by_commitment::<_, _, SectorShape2KiB>(
+        cache_a.path(),
+        cache_c.path()
+    )?);
+
+    // A cache without p_aux is an error, not a mismatch.
+    let empty_cache = tempdir()?;
+    assert!(replicas_equal_by_commitment::<_, _, SectorShape2KiB>(
+        cache_a.path(),
+        empty_cache.path()
+    )
+    .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_assert_matching_comm_c() -> Result<()> {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    let p_aux: PersistentAux<DefaultTreeDomain> = PersistentAux {
+        comm_c: Fr::random(&mut *rng).into(),
+        comm_r_last: Fr::random(&mut *rng).into(),
+    };
+
+    // Caches a and b share comm_c but not comm_r_last.
+    let cache_a = tempdir()?;
+    let cache_b = tempdir()?;
+    write_p_aux(&cache_a, &p_aux)?;
+    write_p_aux(
+        &cache_b,
+        &PersistentAux {
+            comm_c: p_aux.comm_c,
+            comm_r_last: Fr::random(&mut *rng).into(),
+        },
+    )?;
+    assert_matching_comm_c::<_, _, SectorShape2KiB>(cache_a.path(), cache_b.path())?;
+
+    let mismatched_cache = tempdir()?;
+    write_p_aux(
+        &mismatched_cache,
+        &PersistentAux {
+            comm_c: Fr::random(&mut *rng).into(),
+            comm_r_last: p_aux.comm_r_last,
+        },
+    )?;
+    let err =
+        assert_matching_comm_c::<_, _, SectorShape2KiB>(cache_a.path(), mismatched_cache.path())
+            .expect_err("comm_c must not match");
+    assert!(format!("{}", err).contains("comm_c mismatch"));
+
+    // A cache without p_aux is an error.
+    let empty_cache = tempdir()?;
+    assert!(
+        assert_matching_comm_c::<_, _, SectorShape2KiB>(cache_a.path(), empty_cache.path())
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_read_comm_r_last() -> Result<()> {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    let p_aux: PersistentAux<DefaultTreeDomain> = PersistentAux {
+        comm_c: Fr::random(&mut *rng).into(),
+        comm_r_last: Fr::random(&mut *rng).into(),
+    };
+    let cache_dir = tempdir()?;
+    write_p_aux(&cache_dir, &p_aux)?;
+
+    assert_eq!(
+        read_comm_r_last::<_, SectorShape2KiB>(cache_dir.path())?,
+        commitment_from_fr(p_aux.comm_r_last.into())
+    );
+
+    // A cache without p_aux is an error.
+    let empty_cache = tempdir()?;
+    assert!(read_comm_r_last::<_, SectorShape2KiB>(empty_cache.path()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_rebuild_p_aux() -> Result<()> {
+    rebuild_p_aux_for_shape::<SectorShape2KiB>(SECTOR_SIZE_2_KIB)?;
+    // Two base trees, combined through the sub tree level.
+    rebuild_p_aux_for_shape::<SectorShape4KiB>(SECTOR_SIZE_4_KIB)?;
+
+    Ok(())
+}
+
+fn rebuild_p_aux_for_shape<Tree: 'static + MerkleTreeTrait>(sector_size: u64) -> Result<()> {
+    let (_, cache_dir, _sealed_sector_file) = run_seal_pre_commit::<Tree>(sector_size)?;
+
+    let p_aux_path = cache_dir.path().join(CacheKey::PAux.to_string());
+    let p_aux_bytes = std::fs::read(&p_aux_path)?;
+    remove_file(&p_aux_path)?;
+    rebuild_p_aux::<_, Tree>(cache_dir.path())?;
+    assert_eq!(
+        std::fs::read(&p_aux_path)?,
+        p_aux_bytes,
+        "rebuilt p_aux doesn't match the original one"
+    );
+
+    Ok(())
+}
+
+/// Runs both pre commit phases over a random piece filling the sector, and
+/// returns the config, cache directory and replica file of the sealed sector.
+fn run_seal_pre_commit<Tree: 'static + MerkleTreeTrait>(
+    sector_size: u64,
+) -> Result<(PoRepConfig, TempDir, NamedTempFile)> {
+    init_logger();
+
+    let mut rng = XorShiftRng::from_seed(TEST_SEED);
+    let prover_fr: DefaultTreeDomain = Fr::random(&mut rng).into();
+    let mut prover_id = [0u8; 32];
+    prover_id.copy_from_slice(AsRef::<[u8]>::as_ref(&prover_fr));
+
+    let (mut piece_file, _) = generate_piece_file(sector_size)?;
+    let sealed_sector_file = NamedTempFile::new()?;
+    let cache_dir = tempdir()?;
+
+    let config = porep_config(sector_size, ARBITRARY_POREP_ID_V1_1_0, ApiVersion::V1_1_0);
+    let ticket = rng.gen();
+    let sector_id = rng.gen::<u64>().into();
+
+    let (_, phase1_output) = run_seal_pre_commit_phase1::<Tree>(
+        config,
+        prover_id,
+        sector_id,
+        ticket,
+        &cache_dir,
+        &mut piece_file,
+        &sealed_sector_file,
+    )?;
+    seal_pre_commit_phase2(
+        config,
+        phase1_output,
+        cache_dir.path(),
+        sealed_sector_file.path(),
+    )?;
+    validate_cache_for_commit::<_, _, Tree>(cache_dir.path(), sealed_sector_file.path())?;
+
+    Ok((config, cache_dir, sealed_sector_file))
+}
+
+#[test]
+fn test_rebuild_p_aux_errors() -> Result<()> {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    // An existing p_aux is never overwritten.
+    let cache_dir = tempdir()?;
+    let p_aux: PersistentAux<DefaultTreeDomain> = PersistentAux {
+        comm_c: Fr::random(&mut *rng).into(),
+        comm_r_last: Fr::random(&mut *rng).into(),
+    };
+    write_p_aux(&cache_dir, &p_aux)?;
+    let err = rebuild_p_aux::<_, SectorShape2KiB>(cache_dir.path())
+        .expect_err("existing p_aux must not be overwritten");
+    assert!(format!("{}", err).contains("refusing to overwrite existing p_aux"));
+
+    // Without t_aux the tree stores cannot be located.
+    let empty_cache = tempdir()?;
+    assert!(rebuild_p_aux::<_, SectorShape2KiB>(empty_cache.path()).is_err());
+    assert!(!empty_cache.path().join(CacheKey::PAux.to_string()).exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_rebuild_t_aux() -> Result<()> {
+    rebuild_t_aux_for_shape::<SectorShape2KiB>(SECTOR_SIZE_2_KIB, false)?;
+    // Split stores, whose sizes and rows to discard follow the base tree size.
+    rebuild_t_aux_for_shape::<SectorShape4KiB>(SECTOR_SIZE_4_KIB, false)?;
+    rebuild_t_aux_for_shape::<SectorShape16KiB>(SECTOR_SIZE_16_KIB, false)?;
+    // A cleared cache only keeps p_aux, t_aux and tree_r_last.
+    rebuild_t_aux_for_shape::<SectorShape2KiB>(SECTOR_SIZE_2_KIB, true)?;
+    rebuild_t_aux_for_shape::<SectorShape4KiB>(SECTOR_SIZE_4_KIB, true)?;
+
+    Ok(())
+}
+
+fn rebuild_t_aux_for_shape<Tree: 'static + MerkleTreeTrait>(
+    sector_size: u64,
+    clear: bool,
+) -> Result<()> {
+    let (config, cache_dir, _sealed_sector_file) = run_seal_pre_commit::<Tree>(sector_size)?;
+    if clear {
+        clear_cache::<Tree>(cache_dir.path())?;
+    }
+
+    let t_aux_path = cache_dir.path().join(CacheKey::TAux.to_string());
+    let t_aux_bytes = std::fs::read(&t_aux_path)?;
+    remove_file(&t_aux_path)?;
+    rebuild_t_aux::<_, Tree>(cache_dir.path(), config.sector_size)?;
+    assert_eq!(
+        std::fs::read(&t_aux_path)?,
+        t_aux_bytes,
+        "rebuilt t_aux doesn't match the original one"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_rebuild_t_aux_errors() -> Result<()> {
+    // An existing t_aux is never overwritten.
+    let cache_dir = tempdir()?;
+    let t_aux_path = cache_dir.path().join(CacheKey::TAux.to_string());
+    std::fs::write(&t_aux_path, b"t_aux")?;
+    let err = rebuild_t_aux::<_, SectorShape2KiB>(cache_dir.path(), SectorSize(SECTOR_SIZE_2_KIB))
+        .expect_err("existing t_aux must not be overwritten");
+    assert!(format!("{}", err).contains("refusing to overwrite existing t_aux"));
+    assert_eq!(std::fs::read(&t_aux_path)?, b"t_aux");
+
+    // Without the stores there is nothing to rebuild from.
+    let empty_cache = tempdir()?;
+    assert!(
+        rebuild_t_aux::<_, SectorShape2KiB>(empty_cache.path(), SectorSize(SECTOR_SIZE_2_KIB))
+            .is_err()
+    );
+    assert!(!empty_cache.path().join(CacheKey::TAux.to_string()).exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_describe_t_aux() -> Result<()> {
+    let cache_dir = tempdir()?;
+
+    let t_aux = test_t_aux_2kib(cache_dir.path());
+    let t_aux_path = cache_dir.path().join(CacheKey::TAux.to_string());
+    std::fs::write(&t_aux_path, serialize(&t_aux)?)?;
+
+    let description = describe_t_aux::<_, SectorShape2KiB>(cache_dir.path())?;
+    assert_eq!(description.labels.len(), 2);
+    assert_eq!(description.labels[1].id, CacheKey::label_layer(2));
+    assert_eq!(
+        description.tree_d,
+        StoreConfigDescription {
+            id: CacheKey::CommDTree.to_string(),
+            size: Some(127),
+            rows_to_discard: 0,
+        }
+    );
+    assert_eq!(description.tree_c.id, CacheKey::CommCTree.to_string());
+    assert_eq!(description.tree_r_last.rows_to_discard, 1);
+
+    // The description must be serializable for inspection tooling.
+    let json = serde_json::to_string(&description)?;
+    assert!(json.contains("tree-r-last"));
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_t_aux_stores() -> Result<()> {
+    let cache_dir = tempdir()?;
+
+    let t_aux = test_t_aux_2kib(cache_dir.path());
+
+    let err = validate_t_aux_stores(&t_aux, cache_dir.path())
+        .expect_err("validation must fail without any stores");
+    assert!(format!("{:#}", err).contains("missing or inconsistent label store"));
+
+    // Once the labels exist, the first missing tree store is reported.
+    for label in &t_aux.labels.labels {
+        std::fs::write(
+            StoreConfig::data_path(&label.path, &label.id),
+            vec![0u8; 64 * 32],
+        )?;
+    }
+    let err = validate_t_aux_stores(&t_aux, cache_dir.path())
+        .expect_err("validation must fail without tree stores");
+    assert!(format!("{:#}", err).contains("missing or inconsistent tree store tree-d"));
+
+    // A store config without a size is reported rather than panicking.
+    let mut t_aux = t_aux;
+    t_aux.tree_d_config.size = None;
+    std::fs::write(
+        StoreConfig::data_path(&t_aux.tree_d_config.path, &t_aux.tree_d_config.id),
+        vec![0u8; 127 * 32],
+    )?;
+    let err = validate_t_aux_stores(&t_aux, cache_dir.path())
+        .expect_err("validation must fail without a configured store size");
+    let message = format!("{:#}", err);
+    assert!(message.contains("missing or inconsistent tree store tree-d"));
+    assert!(message.contains("disk store size not configured"));
+
+    Ok(())
+}
+
+/// A `t_aux` of a 2KiB sector with two layers, with all stores in `cache_path`.
+fn test_t_aux_2kib(cache_path: &Path) -> TemporaryAux<SectorShape2KiB, DefaultPieceHasher> {
+    let store_config = |id: String, size: usize, rows_to_discard: usize| {
+        let mut config = StoreConfig::new(cache_path, id, rows_to_discard);
+        config.size = Some(size);
+        config
+    };
+
+    TemporaryAux {
+        labels: Labels::new(vec![
+            store_config(CacheKey::label_layer(1), 64, 0),
+            store_config(CacheKey::label_layer(2), 64, 0),
+        ]),
+        tree_d_config: store_config(CacheKey::CommDTree.to_string(), 127, 0),
+        tree_r_last_config: store_config(CacheKey::CommRLastTree.to_string(), 73, 1),
+        tree_c_config: store_config(CacheKey::CommCTree.to_string(), 73, 1),
+        _g: PhantomData,
+    }
+}
+
+#[test]
+fn test_verify_comm_r_streaming() -> Result<()> {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    verify_comm_r_streaming_for_shape::<SectorShape2KiB>(rng, SECTOR_SIZE_2_KIB)?;
+    verify_comm_r_streaming_for_shape::<SectorShape4KiB>(rng, SECTOR_SIZE_4_KIB)?;
+    verify_comm_r_streaming_for_shape::<SectorShape16KiB>(rng, SECTOR_SIZE_16_KIB)?;
+    verify_comm_r_streaming_for_shape::<SectorShape32KiB>(rng, SECTOR_SIZE_32_KIB)?;
+
+    Ok(())
+}
+
+fn verify_comm_r_streaming_for_shape<
+    Tree: 'static + MerkleTreeTrait<Hasher = DefaultTreeHasher>,
+>(
+    rng: &mut XorShiftRng,
+    sector_size: u64,
+) -> Result<()> {
+    let porep_config = PoRepConfig {
+        sector_size: SectorSize(sector_size),
+        partitions: PoRepProofPartitions(1),
+        porep_id: ARBITRARY_POREP_ID_V1_1_0,
+        api_version: ApiVersion::V1_1_0,
+    };
+
+    // Build the reference tree_r_last over random replica nodes.
+    let (data, tree) = generate_tree::<
+        DiskTree<DefaultTreeHasher, Tree::Arity, Tree::SubTreeArity, Tree::TopTreeArity>,
+        _,
+    >(rng, sector_size as usize / NODE_SIZE, None);
+    let mut replica_file = NamedTempFile::new()?;
+    replica_file.write_all(&data)?;
+    replica_file.flush()?;
+
+    let comm_c: DefaultTreeDomain = Fr::random(&mut *rng).into();
+    let comm_r_safe = <DefaultTreeHasher as Hasher>::Function::hash2(&comm_c, &tree.root());
+    let comm_c = commitment_from_fr(comm_c.into());
+    let comm_r = commitment_from_fr(comm_r_safe.into());
+
+    assert!(verify_comm_r_streaming::<_, Tree>(
+        porep_config,
+        replica_file.path(),
+        comm_c,
+        comm_r
+    )?);
+
+    let other_comm_c = commitment_from_fr(Fr::random(&mut *rng));
+    assert!(!verify_comm_r_streaming::<_, Tree>(
+        porep_config,
+        replica_file.path(),
+        other_comm_c,
+        comm_r
+    )?);
+
+    // A truncated replica is an error, not a mismatch.
+    replica_file
+        .as_file()
+        .set_len(sector_size - NODE_SIZE as u64)?;
+    assert!(
+        verify_comm_r_streaming::<_, Tree>(porep_config, replica_file.path(), comm_c, comm_r)
+            .is_err()
+    );
+
+    // A node that is not a field element is an error, not a panic.
+    replica_file.as_file().set_len(sector_size)?;
+    replica_file.seek(SeekFrom::Start(NODE_SIZE as u64))?;
+    replica_file.write_all(&[0xff; NODE_SIZE])?;
+    replica_file.flush()?;
+    assert!(
+        verify_comm_r_streaming::<_, Tree>(porep_config, replica_file.path(), comm_c, comm_r)
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_merkle_root() -> Result<()> {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    merkle_root_for_tree::<PoseidonHasher, U2>(rng, HasherKind::Poseidon, 256)?;
+    merkle_root_for_tree::<PoseidonHasher, U4>(rng, HasherKind::Poseidon, 256)?;
+    merkle_root_for_tree::<PoseidonHasher, U8>(rng, HasherKind::Poseidon, 512)?;
+    merkle_root_for_tree::<PoseidonHasher, U16>(rng, HasherKind::Poseidon, 256)?;
+    merkle_root_for_tree::<Sha256Hasher, U2>(rng, HasherKind::Sha256, 256)?;
+    merkle_root_for_tree::<Sha256Hasher, U8>(rng, HasherKind::Sha256, 512)?;
+    merkle_root_for_tree::<Blake2sHasher, U2>(rng, HasherKind::Blake2s, 256)?;
+    merkle_root_for_tree::<Blake2sHasher, U4>(rng, HasherKind::Blake2s, 256)?;
+
+    // A binary sha256 tree over a padded piece is its piece commitment.
+    let piece_size = UnpaddedBytesAmount::from(PaddedBytesAmount(2048));
+    let piece_bytes: Vec<u8> = (0..u64::from(piece_size)).map(|_| rng.gen()).collect();
+    let mut padded_file = NamedTempFile::new()?;
+    let (piece_info, _) = add_piece(&piece_bytes[..], &mut padded_file, piece_size, &[])?;
+    padded_file.flush()?;
+    assert_eq!(
+        merkle_root(padded_file.path(), HasherKind::Sha256, 2)?,
+        piece_info.commitment
+    );
+
+    // Unsupported arities.
+    assert!(merkle_root(padded_file.path(), HasherKind::Sha256, 3).is_err());
+    assert!(merkle_root(padded_file.path(), HasherKind::Poseidon, 32).is_err());
+
+    // 64 nodes are not a power of 16; 3 nodes are not a power of two.
+    let mut data_file = NamedTempFile::new()?;
+    data_file.write_all(&[0u8; 64 * NODE_SIZE])?;
+    data_file.flush()?;
+    assert!(merkle_root(data_file.path(), HasherKind::Sha256, 16).is_err());
+    data_file.as_file().set_len(3 * NODE_SIZE as u64)?;
+    assert!(merkle_root(data_file.path(), HasherKind::Sha256, 2).is_err());
+    data_file.as_file().set_len(NODE_SIZE as u64 + 1)?;
+    assert!(merkle_root(data_file.path(), HasherKind::Sha256, 2).is_err());
+
+    // Poseidon leafs must be field elements.
+    let mut invalid_file = NamedTempFile::new()?;
+    invalid_file.write_all(&[0xff; 2 * NODE_SIZE])?;
+    invalid_file.flush()?;
+    assert!(merkle_root(invalid_file.path(), HasherKind::Poseidon, 2).is_err());
+    assert!(merkle_root(invalid_file.path(), HasherKind::Blake2s, 2).is_ok());
+
+    Ok(())
+}
+
+fn merkle_root_for_tree<H: 'static + Hasher, U: 'static + PoseidonArity>(
+    rng: &mut XorShiftRng,
+    hasher: HasherKind,
+    nodes: usize,
+) -> Result<()> {
+    let (data, tree) = generate_tree::<DiskTree<H, U, U0, U0>, _>(rng, nodes, None);
+    let mut data_file = NamedTempFile::new()?;
+    data_file.write_all(&data)?;
+    data_file.flush()?;
+
+    let root = merkle_root(data_file.path(), hasher, U::to_usize())?;
+    assert_eq!(&root[..], tree.root().as_ref());
+
+    Ok(())
+}
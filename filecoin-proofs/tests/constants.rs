@@ -1,9 +1,10 @@
 use filecoin_proofs::{
-    with_shape, SECTOR_SIZE_16_MIB, SECTOR_SIZE_1_GIB, SECTOR_SIZE_2_KIB, SECTOR_SIZE_32_GIB,
+    tree_arities, with_shape, PoRepConfig, PoRepProofPartitions, SectorSize, TreeArity,
+    SECTOR_SIZE_16_MIB, SECTOR_SIZE_1_GIB, SECTOR_SIZE_2_KIB, SECTOR_SIZE_32_GIB,
     SECTOR_SIZE_4_KIB, SECTOR_SIZE_512_MIB, SECTOR_SIZE_64_GIB, SECTOR_SIZE_8_MIB,
 };
 use generic_array::typenum::Unsigned;
-use storage_proofs_core::merkle::MerkleTreeTrait;
+use storage_proofs_core::{api_version::ApiVersion, merkle::MerkleTreeTrait};
 
 fn canonical_shape(sector_size: u64) -> (usize, usize, usize) {
     // This could perhaps be cleaned up, but I think it expresses the intended constraints
@@ -83,3 +84,47 @@ fn test_with_shape_macro_aux(sector_size: u64) {
         sector_size, arities, expected
     );
 }
+
+#[test]
+fn test_tree_arities() {
+    // The test-only 16KiB and 32KiB shapes don't follow the canonical shape.
+    for sector_size in &[
+        SECTOR_SIZE_2_KIB,
+        SECTOR_SIZE_4_KIB,
+        SECTOR_SIZE_8_MIB,
+        SECTOR_SIZE_16_MIB,
+        SECTOR_SIZE_512_MIB,
+        SECTOR_SIZE_1_GIB,
+        SECTOR_SIZE_32_GIB,
+        SECTOR_SIZE_64_GIB,
+    ] {
+        let porep_config = PoRepConfig {
+            sector_size: SectorSize(*sector_size),
+            partitions: PoRepProofPartitions(1),
+            porep_id: [0; 32],
+            api_version: ApiVersion::V1_1_0,
+        };
+        let arities = tree_arities(porep_config).expect("tree_arities failed");
+
+        let (base, sub, top) = canonical_shape(*sector_size);
+        let expected = TreeArity { base, sub, top };
+        assert_eq!(
+            arities.d,
+            TreeArity {
+                base: 2,
+                sub: 0,
+                top: 0
+            }
+        );
+        assert_eq!(arities.c, expected);
+        assert_eq!(arities.r, expected);
+    }
+
+    let porep_config = PoRepConfig {
+        sector_size: SectorSize(SECTOR_SIZE_2_KIB * 4),
+        partitions: PoRepProofPartitions(1),
+        porep_id: [0; 32],
+        api_version: ApiVersion::V1_1_0,
+    };
+    assert!(tree_arities(porep_config).is_err());
+}
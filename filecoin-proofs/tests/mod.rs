@@ -3,8 +3,8 @@ use std::panic::panic_any;
 use blstrs::Scalar as Fr;
 use ff::Field;
 use filecoin_proofs::{
-    as_safe_commitment, verify_seal, DefaultOctLCTree, DefaultTreeDomain, PoRepConfig,
-    PoRepProofPartitions, SectorSize, POREP_PARTITIONS, SECTOR_SIZE_2_KIB, TEST_SEED,
+    as_safe_commitment, get_seal_inputs, verify_seal, DefaultOctLCTree, DefaultTreeDomain,
+    PoRepConfig, PoRepProofPartitions, SectorSize, POREP_PARTITIONS, SECTOR_SIZE_2_KIB, TEST_SEED,
 };
 use fr32::bytes_into_fr;
 use rand::SeedableRng;
@@ -158,3 +158,60 @@ fn test_random_domain_element() {
         assert_eq!(back, random_el);
     }
 }
+
+#[test]
+fn test_seal_inputs_reject_non_canonical_comm_d() {
+    // The BLS12-381 scalar field modulus, little-endian.
+    let modulus: [u8; 32] = [
+        0x01, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0x02, 0xa4, 0xbd,
+        0x53, 0x05, 0xd8, 0xa1, 0x09, 0x08, 0xd8, 0x39, 0x33, 0x48, 0x7d, 0x9d, 0x29, 0x53, 0xa7,
+        0xed, 0x73,
+    ];
+    let mut modulus_minus_one = modulus;
+    modulus_minus_one[0] = 0x00;
+    let mut modulus_plus_one = modulus;
+    modulus_plus_one[0] = 0x02;
+
+    assert!(bytes_into_fr(&modulus_minus_one).is_ok(), "tripwire");
+    assert!(bytes_into_fr(&modulus).is_err(), "tripwire");
+    assert!(bytes_into_fr(&modulus_plus_one).is_err(), "tripwire");
+
+    let porep_config = PoRepConfig {
+        sector_size: SectorSize(SECTOR_SIZE_2_KIB),
+        partitions: PoRepProofPartitions(
+            *POREP_PARTITIONS
+                .read()
+                .expect("POREP_PARTITIONS poisoned")
+                .get(&SECTOR_SIZE_2_KIB)
+                .expect("unknown sector size"),
+        ),
+        porep_id: [87; 32],
+        api_version: ApiVersion::V1_1_0,
+    };
+
+    for comm_d in &[modulus, modulus_plus_one] {
+        let result = get_seal_inputs::<DefaultOctLCTree>(
+            porep_config,
+            [1; 32],
+            *comm_d,
+            [0; 32],
+            SectorId::from(0),
+            [0; 32],
+            [0; 32],
+        );
+
+        if let Err(err) = result {
+            let needle = "Invalid commitment (comm_d)";
+            let haystack = format!("{:#}", err);
+
+            assert!(
+                haystack.contains(needle),
+                "\"{}\" did not contain \"{}\"",
+                haystack,
+                needle,
+            );
+        } else {
+            panic_any("should have failed comm_d to Fr32 conversion");
+        }
+    }
+}
@@ -1,16 +1,17 @@
-use std::io::{Cursor, Read};
+use std::io::{Cursor, Read, Write};
 use std::iter::Iterator;
 
 use anyhow::Result;
 use blstrs::Scalar as Fr;
 use filecoin_proofs::{
-    add_piece, commitment_from_fr,
+    add_piece, commitment_from_fr, generate_piece_commitment,
     pieces::{
-        compute_comm_d, get_piece_alignment, get_piece_start_byte, piece_hash, verify_pieces,
-        zero_padding, EmptySource, PieceAlignment,
+        compute_comm_d, get_piece_alignment, get_piece_start_byte, piece_hash, piece_node_range,
+        verify_pieces, zero_padding, EmptySource, PieceAlignment,
     },
-    Commitment, DataTree, DefaultPieceHasher, PaddedBytesAmount, PieceInfo, SectorSize,
-    UnpaddedByteIndex, UnpaddedBytesAmount, DRG_DEGREE, EXP_DEGREE, TEST_SEED,
+    verify_piece_commitment, Commitment, DataTree, DefaultPieceHasher, PaddedBytesAmount,
+    PieceInfo, SectorSize, UnpaddedByteIndex, UnpaddedBytesAmount, DRG_DEGREE, EXP_DEGREE,
+    TEST_SEED,
 };
 use rand::{Rng, RngCore, SeedableRng};
 use rand_xorshift::XorShiftRng;
@@ -18,6 +19,7 @@ use storage_proofs_core::{
     api_version::ApiVersion, drgraph::Graph, merkle::create_base_merkle_tree, util::NODE_SIZE,
 };
 use storage_proofs_porep::stacked::StackedBucketGraph;
+use tempfile::NamedTempFile;
 
 #[test]
 fn test_empty_source() {
@@ -48,6 +50,62 @@ fn test_compute_comm_d_empty() {
     );
 }
 
+#[test]
+fn test_piece_cid() -> Result<()> {
+    // The piece CID of an empty 2KiB sector's zero piece.
+    let zero_piece = zero_padding(UnpaddedBytesAmount::from(SectorSize(2048)))?;
+    let cid = "baga6ea4seaqpy7usqklokfx2vxuynmupslkeutzexe2uqurdg5vhtebhxqmpqmy";
+    assert_eq!(zero_piece.to_piece_cid(), cid);
+    assert_eq!(PieceInfo::from_piece_cid(cid, zero_piece.size)?, zero_piece);
+
+    let mut commitment = [0u8; 32];
+    for (i, byte) in commitment.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let piece_info = PieceInfo::new(commitment, UnpaddedBytesAmount(127))?;
+    let cid = "baga6ea4seaqaaaicamcakbqhbaequcymbuha6earcijrifiwc4mbsgq3dqor4hy";
+    assert_eq!(piece_info.to_piece_cid(), cid);
+    assert_eq!(
+        PieceInfo::from_piece_cid(&piece_info.to_piece_cid(), piece_info.size)?,
+        piece_info
+    );
+
+    // Not base32, bad alphabet, truncated, and a different multihash code.
+    for invalid in &[
+        "zaga6ea4seaqaaaicamcakbqhbaequcymbuha6earcijrifiwc4mbsgq3dqor4hy",
+        "baga6ea4seaqaaaicamcakbqhbaequcymbuha6earcijrifiwc4mbsgq3dqor4h1",
+        "baga6ea4seaqaaaicamcakbqhbaequcymbuha6earcijrifiwc4mbsgq3dqor4",
+        "baga6ea4qeaqaaaicamcakbqhbaequcymbuha6earcijrifiwc4mbsgq3dqor4hy",
+    ] {
+        assert!(PieceInfo::from_piece_cid(invalid, piece_info.size).is_err());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_verify_pieces_empty() -> Result<()> {
+    for sector_size in &[
+        SectorSize(128),
+        SectorSize(2048),
+        SectorSize(1024 * 8 * 128),
+    ] {
+        // No pieces is the same as a single zero piece covering the whole sector.
+        let comm_d = compute_comm_d(*sector_size, &[])?;
+        let full_sector_padding = zero_padding(UnpaddedBytesAmount::from(*sector_size))?;
+        assert_eq!(comm_d, full_sector_padding.commitment);
+        assert_eq!(
+            comm_d,
+            compute_comm_d(*sector_size, &[full_sector_padding])?
+        );
+
+        assert!(verify_pieces(&comm_d, &[], *sector_size)?);
+        assert!(!verify_pieces(&[1; 32], &[], *sector_size)?);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_get_piece_alignment() {
     let table = vec![
@@ -103,6 +161,37 @@ fn test_get_piece_start_byte() {
     );
 }
 
+#[test]
+fn test_piece_node_range() -> Result<()> {
+    let sizes = [127, 254, 127, 508];
+    let piece_infos = sizes
+        .iter()
+        .map(|size| PieceInfo::new([1; 32], UnpaddedBytesAmount(*size)))
+        .collect::<Result<Vec<_>>>()?;
+
+    // 127 | 127 (left padding) + 254 | 127 + 381 (left padding) | 508
+    assert_eq!(piece_node_range(&piece_infos, 0)?, (0, 4));
+    assert_eq!(piece_node_range(&piece_infos, 1)?, (8, 16));
+    assert_eq!(piece_node_range(&piece_infos, 2)?, (16, 20));
+    assert_eq!(piece_node_range(&piece_infos, 3)?, (32, 48));
+
+    // The range starts where the piece's data starts.
+    let piece_lengths: Vec<_> = piece_infos.iter().map(|p| p.size).collect();
+    for index in 0..piece_infos.len() {
+        let start_byte = get_piece_start_byte(&piece_lengths[..index], piece_lengths[index]);
+        let start_node = usize::from(PaddedBytesAmount::from(UnpaddedBytesAmount::from(
+            start_byte,
+        ))) / NODE_SIZE;
+        assert_eq!(piece_node_range(&piece_infos, index)?.0, start_node);
+    }
+
+    assert!(piece_node_range(&piece_infos, piece_infos.len()).is_err());
+    let invalid = [PieceInfo::new([1; 32], UnpaddedBytesAmount(127 * 3))?];
+    assert!(piece_node_range(&invalid, 0).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_verify_simple_pieces() {
     let rng = &mut XorShiftRng::from_seed(TEST_SEED);
@@ -368,6 +457,55 @@ fn test_verify_random_pieces() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_verify_piece_commitment() -> Result<()> {
+    let rng = &mut XorShiftRng::from_seed(TEST_SEED);
+
+    let piece_size = UnpaddedBytesAmount(127 * 8);
+    let mut piece_bytes = vec![0u8; u64::from(piece_size) as usize];
+    rng.fill_bytes(&mut piece_bytes);
+
+    // A piece file holding exactly `piece_size` bytes.
+    let piece_info = generate_piece_commitment(Cursor::new(&piece_bytes), piece_size)?;
+    let mut piece_file = NamedTempFile::new()?;
+    piece_file.write_all(&piece_bytes)?;
+    assert!(verify_piece_commitment(piece_file.path(), &piece_info)?);
+
+    // A single changed byte must not match.
+    let mut tampered_bytes = piece_bytes.clone();
+    tampered_bytes[17] ^= 1;
+    let mut tampered_file = NamedTempFile::new()?;
+    tampered_file.write_all(&tampered_bytes)?;
+    assert!(!verify_piece_commitment(tampered_file.path(), &piece_info)?);
+
+    // A short piece file is zero-filled up to the unpadded piece size.
+    let short_len = 1000;
+    let mut zero_filled_bytes = piece_bytes[..short_len].to_vec();
+    zero_filled_bytes.resize(u64::from(piece_size) as usize, 0);
+    let short_piece_info = generate_piece_commitment(Cursor::new(&zero_filled_bytes), piece_size)?;
+    let mut short_file = NamedTempFile::new()?;
+    short_file.write_all(&piece_bytes[..short_len])?;
+    assert!(verify_piece_commitment(
+        short_file.path(),
+        &short_piece_info
+    )?);
+
+    // More bytes than the unpadded piece size is an error.
+    let mut long_file = NamedTempFile::new()?;
+    long_file.write_all(&piece_bytes)?;
+    long_file.write_all(&[0u8])?;
+    assert!(verify_piece_commitment(long_file.path(), &piece_info).is_err());
+
+    // Passing the padded size where the unpadded size is expected is an error.
+    let padded_size_info = PieceInfo {
+        commitment: piece_info.commitment,
+        size: UnpaddedBytesAmount(u64::from(PaddedBytesAmount::from(piece_size))),
+    };
+    assert!(verify_piece_commitment(piece_file.path(), &padded_size_info).is_err());
+
+    Ok(())
+}
+
 fn build_sector(
     piece_sizes: &[UnpaddedBytesAmount],
     sector_size: SectorSize,